@@ -0,0 +1,52 @@
+//! Compares the polynomial multiplication algorithms at increasing degrees.
+//!
+//! Run with `cargo run --release --example multiply_benchmark`.
+
+#![allow(clippy::needless_return)]
+
+use std::time::{Duration, Instant};
+use rand::prelude::*;
+use num::BigInt;
+use cs225_impl::VecPoly;
+use cs225_impl::field::Fp;
+use cs225_impl::multiply::{karatsuba, schoolbook};
+
+type F = Fp<998244353>;
+
+/// Returns the average time of running f, repeating it until at least 200ms have passed.
+fn time<O, G: FnMut() -> O>(mut f: G) -> Duration {
+    let start = Instant::now();
+    let mut runs = 0;
+    while runs == 0 || start.elapsed() < Duration::from_millis(200) {
+        std::hint::black_box(f());
+        runs += 1;
+    }
+    return start.elapsed() / runs
+}
+
+fn main() {
+    let mut rng = StdRng::seed_from_u64(0);
+    println!("GF(998244353), both factors with n coefficients");
+    println!("{:>8} {:>14} {:>14} {:>14}", "n", "schoolbook", "karatsuba", "ntt");
+    for &n in [16, 64, 256, 1024, 4096].iter() {
+        let a = VecPoly::from_lowest_first((0..n).map(|_| rng.gen::<F>()).collect());
+        let b = VecPoly::from_lowest_first((0..n).map(|_| rng.gen::<F>()).collect());
+        let schoolbook_time = time(|| schoolbook(a.coefficients(), b.coefficients()));
+        let karatsuba_time = time(|| karatsuba(a.coefficients(), b.coefficients()));
+        let ntt_time = time(|| a.mul_ntt(&b));
+        println!("{:>8} {:>14?} {:>14?} {:>14?}", n, schoolbook_time, karatsuba_time, ntt_time);
+    }
+
+    println!();
+    println!("integers of about 256 bits, both factors with n coefficients");
+    println!("{:>8} {:>14} {:>14} {:>14}", "n", "schoolbook", "karatsuba", "multimodular");
+    for &n in [16, 64, 256, 1024].iter() {
+        let mut big = || (0..4).fold(BigInt::from(1), |acc, _| acc * BigInt::from(rng.gen::<u64>()));
+        let a = VecPoly::from_lowest_first((0..n).map(|_| big()).collect());
+        let b = VecPoly::from_lowest_first((0..n).map(|_| big()).collect());
+        let schoolbook_time = time(|| schoolbook(a.coefficients(), b.coefficients()));
+        let karatsuba_time = time(|| karatsuba(a.coefficients(), b.coefficients()));
+        let multimodular_time = time(|| a.mul_multimodular(&b));
+        println!("{:>8} {:>14?} {:>14?} {:>14?}", n, schoolbook_time, karatsuba_time, multimodular_time);
+    }
+}
@@ -0,0 +1,219 @@
+//! The Agrawal-Biswas primality test, which is polynomial identity testing in Z_n[x].
+//!
+//! n is prime exactly when (x + 1)^n = x^n + 1 in Z_n[x], but the two sides have n + 1
+//! coefficients, far too many to compare. Like `test_zero_mod_primes_with_rng` fingerprints an
+//! integer polynomial modulo a random prime, this fingerprints both sides modulo a random monic
+//! polynomial Q of degree d, which takes O(log n) products of polynomials of degree less than d.
+//!
+//! If n is composite but not a prime power, it has a prime factor p with p^a || n, and the
+//! coefficient binom(n, p^a) of P(x) = (x + 1)^n - x^n - 1 is not divisible by p, so P is nonzero
+//! modulo p. Q modulo p is a uniformly random monic polynomial over GF(p), which is irreducible
+//! with probability at least 1/(2d), and P has at most n/d irreducible factors of degree d, out
+//! of p^d >= 2^d monic polynomials. With d = ceil(log2 n) + 2 a trial therefore catches n with
+//! probability at least 1/(2d) - n/(d 2^d) >= 1/(4d). Prime powers are caught separately.
+//!
+//! Z_n is not a field when n is composite, so the coefficients stay plain integers reduced with
+//! the modular arithmetic of `PrimalityCandidate`, and the products are reduced modulo Q by
+//! division without inverses, which works since Q is monic.
+
+use rand::prelude::*;
+use num::bigint::ToBigUint;
+use num::integer::Roots;
+use crate::VecPoly;
+use crate::pit::trials_for_ratio;
+use crate::primality::PrimalityCandidate;
+
+/// AgrawalBiswas is the outcome of the Agrawal-Biswas test.
+#[derive(Debug, Clone, PartialEq)]
+pub enum AgrawalBiswas<N> {
+    /// n is root^exponent with exponent >= 2, so it is composite.
+    PerfectPower { root: N, exponent: u32 },
+    /// (x + 1)^n differs from x^n + 1 modulo n and the monic polynomial with these coefficients,
+    /// lowest order term first, so n is composite. This can be checked with
+    /// `agrawal_biswas_witness`.
+    Composite { modulus: Vec<N> },
+    /// Both sides agreed modulo trials random polynomials. If n is composite, this happens with
+    /// probability at most error_bound.
+    ProbablyPrime { trials: usize, error_bound: f64 },
+}
+
+impl<N> AgrawalBiswas<N> {
+    /// Returns whether the test found no evidence that n is composite.
+    pub fn is_probably_prime(&self) -> bool {
+        return matches!(self, AgrawalBiswas::ProbablyPrime { .. })
+    }
+}
+
+/// Returns the product of a and b with the coefficients reduced mod n, assuming theirs are.
+fn multiply_mod<N: PrimalityCandidate>(a: &VecPoly<N>, b: &VecPoly<N>, n: &N) -> VecPoly<N> {
+    let (a, b) = (a.coefficients(), b.coefficients());
+    if a.is_empty() || b.is_empty() {
+        return VecPoly::from_lowest_first(vec![])
+    }
+    let mut product = vec![N::zero(); a.len() + b.len() - 1];
+    for (i, x) in a.iter().enumerate() {
+        for (j, y) in b.iter().enumerate() {
+            product[i + j] = product[i + j].add_mod(&x.mul_mod(y, n), n);
+        }
+    }
+    return VecPoly::from_lowest_first(product)
+}
+
+/// Returns the remainder of poly modulo n and the monic modulus, given by its coefficients lowest
+/// order term first and reduced mod n. Dividing by a leading coefficient of one needs no
+/// inverses, which Z_n lacks when n is composite.
+fn rem_monic_mod<N: PrimalityCandidate>(poly: &VecPoly<N>, modulus: &[N], n: &N) -> VecPoly<N> {
+    let degree = modulus.len() - 1;
+    let mut remainder: Vec<N> = poly.coefficients().iter().map(|c| c.mod_floor(n)).collect();
+    for shift in (0..remainder.len().saturating_sub(degree)).rev() {
+        let factor = std::mem::replace(&mut remainder[shift + degree], N::zero());
+        for (i, c) in modulus[..degree].iter().enumerate() {
+            remainder[shift + i] = remainder[shift + i].sub_mod(&factor.mul_mod(c, n), n);
+        }
+    }
+    remainder.truncate(degree);
+    return VecPoly::from_lowest_first(remainder)
+}
+
+/// Returns whether (x + 1)^n and x^n + 1 differ modulo n and the monic polynomial modulus,
+/// given by its coefficients lowest order term first, which proves that n is composite. Panics
+/// if n < 2 or the modulus is not monic of degree at least one.
+pub fn agrawal_biswas_witness<N: PrimalityCandidate + ToBigUint>(n: &N, modulus: &[N]) -> bool {
+    assert!(*n >= N::from(2), "primality is only defined for n >= 2");
+    assert!(modulus.len() >= 2 && modulus.last().is_some_and(|c| c.is_one()), "the modulus must be monic with positive degree");
+    let modulus: Vec<N> = modulus.iter().map(|c| c.mod_floor(n)).collect();
+    let exp = n.to_biguint().expect("an unsigned integer is a BigUint");
+    let power = |base: Vec<N>| VecPoly::from_lowest_first(base).pow_reduce(&exp, |a, b| multiply_mod(a, b, n), |poly| rem_monic_mod(poly, &modulus, n));
+    let one = N::one().mod_floor(n);
+    // x^n mod modulus has degree below that of modulus, so adding one leaves it reduced
+    let mut right = power(vec![N::zero(), one.clone()]).coefficients().to_vec();
+    if right.is_empty() {
+        right.push(N::zero());
+    }
+    right[0] = right[0].add_mod(&one, n);
+    let right = VecPoly::from_lowest_first(right);
+    let left = power(vec![one.clone(), one]);
+    return left != right
+}
+
+/// Returns root and exponent >= 2 with n = root^exponent and the largest such exponent, or None
+/// if n is not a perfect power.
+fn perfect_power<N: PrimalityCandidate + Roots>(n: &N) -> Option<(N, u32)> {
+    // the exponent is below the number of bits, since 2^bits > n
+    let bits = n.bits() as u32;
+    return (2..bits).rev().map(|k| (n.nth_root(k), k)).find(|(root, k)| num::pow(root.clone(), *k as usize) == *n)
+}
+
+/// Returns the degree ceil(log2 n) + 2 of the random moduli, which is the number of bits of
+/// n - 1 plus two.
+fn modulus_degree<N: PrimalityCandidate>(n: &N) -> u64 {
+    return (n.clone() - N::one()).bits() + 2
+}
+
+/// Returns the bound 1 - 1/(4d) on the probability that a composite n passes one trial.
+fn per_trial_error(degree: u64) -> f64 {
+    return 1.0 - 1.0 / (4 * degree) as f64
+}
+
+/// Tests whether n is prime by comparing (x + 1)^n with x^n + 1 modulo trials random monic
+/// polynomials of degree ceil(log2 n) + 2 over Z_n, drawn from rng. A composite n passes a trial
+/// with probability at most 1 - 1/(4d), which is far from tight, so the error bound of the
+/// verdict shrinks slowly with trials. Panics if n < 2.
+pub fn agrawal_biswas_test_with_rng<N, R>(n: &N, trials: usize, rng: &mut R) -> AgrawalBiswas<N> where
+    N: PrimalityCandidate + Roots + ToBigUint,
+    R: Rng + ?Sized {
+    assert!(*n >= N::from(2), "primality is only defined for n >= 2");
+    if let Some((root, exponent)) = perfect_power(n) {
+        return AgrawalBiswas::PerfectPower { root, exponent }
+    }
+    let d = modulus_degree(n);
+    for _ in 0..trials {
+        let mut modulus: Vec<N> = (0..d).map(|_| N::random_below(n, rng)).collect();
+        modulus.push(N::one());
+        if agrawal_biswas_witness(n, &modulus) {
+            return AgrawalBiswas::Composite { modulus }
+        }
+    }
+    return AgrawalBiswas::ProbablyPrime { trials, error_bound: per_trial_error(d).powi(trials as i32) }
+}
+
+/// Runs `agrawal_biswas_test_with_rng` with enough trials that a composite n passes with
+/// probability at most error. That takes about 4d ln(1/error) trials, around 5500 for a 64 bit n
+/// and error 10^-9. Panics if n < 2 or error is not in (0, 1).
+pub fn agrawal_biswas_test_to_error_with_rng<N, R>(n: &N, error: f64, rng: &mut R) -> AgrawalBiswas<N> where
+    N: PrimalityCandidate + Roots + ToBigUint,
+    R: Rng + ?Sized {
+    assert!(*n >= N::from(2), "primality is only defined for n >= 2");
+    let trials = trials_for_ratio(per_trial_error(modulus_degree(n)), error).expect("a trial catches a composite with positive probability");
+    return agrawal_biswas_test_with_rng(n, trials, rng)
+}
+
+#[test]
+fn agrees_with_miller_rabin_on_small_integers() {
+    use crate::primality::is_prime;
+
+    let mut rng = StdRng::seed_from_u64(40);
+    for n in 2..1500u64 {
+        let verdict = agrawal_biswas_test_with_rng(&n, 4, &mut rng);
+        assert_eq!(verdict.is_probably_prime(), is_prime(n), "{}: {:?}", n, verdict);
+        match verdict {
+            AgrawalBiswas::PerfectPower { root, exponent } => assert_eq!(root.pow(exponent), n),
+            AgrawalBiswas::Composite { modulus } => assert!(agrawal_biswas_witness(&n, &modulus)),
+            AgrawalBiswas::ProbablyPrime { trials, error_bound } => {
+                assert_eq!(trials, 4);
+                assert!(error_bound < 1.0);
+            }
+        }
+    }
+    assert_eq!(agrawal_biswas_test_with_rng(&59049u64, 1, &mut rng), AgrawalBiswas::PerfectPower { root: 3, exponent: 10 });
+    // any modulus works for a prime, even one of degree one
+    assert!(!agrawal_biswas_witness(&101u64, &[5, 1]));
+}
+
+#[test]
+fn catches_carmichael_numbers_and_large_composites() {
+    use num::BigUint;
+    use crate::primality::{is_prime, miller_rabin_test_with_rng};
+
+    let mut rng = StdRng::seed_from_u64(41);
+    for &n in [561u64, 1105, 1729, 2465, 2821, 6601, 8911, 118901521].iter() {
+        assert!(miller_rabin_test_with_rng(&n, 1e-9, &mut rng).witness().is_some());
+        assert!(!agrawal_biswas_test_with_rng(&n, 8, &mut rng).is_probably_prime(), "{}", n);
+    }
+    for _ in 0..20 {
+        let n = rng.gen_range(1u64 << 24, 1 << 25);
+        assert_eq!(agrawal_biswas_test_with_rng(&n, 2, &mut rng).is_probably_prime(), is_prime(n), "{}", n);
+    }
+    let p = BigUint::from(2147483647u64);
+    assert!(agrawal_biswas_test_with_rng(&p, 1, &mut rng).is_probably_prime());
+    // 37 * 73 * 109
+    let carmichael = BigUint::from(294409u32);
+    assert!(!agrawal_biswas_test_with_rng(&carmichael, 4, &mut rng).is_probably_prime());
+    assert!(!agrawal_biswas_test_with_rng(&(&p * &p), 1, &mut rng).is_probably_prime());
+}
+
+#[test]
+fn handles_integers_near_the_top_of_u64() {
+    let mut rng = StdRng::seed_from_u64(42);
+    // the largest 64 bit prime, where d is 66
+    assert!(agrawal_biswas_test_with_rng(&18446744073709551557u64, 1, &mut rng).is_probably_prime());
+    // 3 * 5 * 17 * 257 * 641 * 65537 * 6700417
+    let verdict = agrawal_biswas_test_with_rng(&u64::MAX, 4, &mut rng);
+    assert!(matches!(&verdict, AgrawalBiswas::Composite { modulus } if modulus.len() == 67), "{:?}", verdict);
+    // (2^32 - 5)^2
+    assert_eq!(agrawal_biswas_test_with_rng(&18446744030759878681u64, 1, &mut rng), AgrawalBiswas::PerfectPower { root: 4294967291, exponent: 2 });
+}
+
+#[test]
+fn target_errors_pick_the_number_of_trials() {
+    let mut rng = StdRng::seed_from_u64(43);
+    // d = 9 for n = 101, so each trial passes a composite with probability at most 35/36
+    match agrawal_biswas_test_to_error_with_rng(&101u64, 0.01, &mut rng) {
+        AgrawalBiswas::ProbablyPrime { trials, error_bound } => {
+            assert_eq!(trials, 164);
+            assert!(error_bound <= 0.01 && error_bound > 0.01 * 35.0 / 36.0, "{}", error_bound);
+        }
+        verdict => panic!("unexpected verdict {:?}", verdict),
+    }
+    assert!(!agrawal_biswas_test_to_error_with_rng(&561u64, 1e-6, &mut rng).is_probably_prime());
+}
@@ -0,0 +1,49 @@
+//! The algebraic structures that the algorithms in this crate need from their coefficients.
+//!
+//! The Schwartz-Zippel lemma only needs the coefficients to form an integral domain: a nonzero
+//! polynomial of total degree d over an integral domain vanishes on at most a d/|S| fraction of
+//! S^n for any finite subset S. Division, and everything built on it, needs a field.
+
+use num::{Zero, One, BigInt, BigRational};
+use std::ops::{Add, Sub, Mul, Div};
+
+/// Ring is a commutative ring with identity. Every type with the right operations is assumed to
+/// be one.
+pub trait Ring: Clone + Zero + One + Add<Output=Self> + Sub<Output=Self> + Mul<Output=Self> {}
+
+impl<T> Ring for T where
+    T: Clone + Zero + One + Add<Output=T> + Sub<Output=T> + Mul<Output=T> {}
+
+/// IntegralDomain is a ring without zero divisors: if a * b = 0 then a = 0 or b = 0.
+///
+/// This can't be checked by the compiler, so it is implemented by hand for the types where it
+/// holds. Machine integers only satisfy it as long as nothing overflows.
+pub trait IntegralDomain: Ring {}
+
+/// Field is an integral domain where every nonzero element has a multiplicative inverse.
+pub trait Field: IntegralDomain + Div<Output=Self> {
+    /// Returns the product of two polynomials given by their coefficients, lowest order term
+    /// first. This is `VecPoly::mul_fast`, so a field with a faster method than Karatsuba can
+    /// provide it here.
+    fn multiply_polynomials(a: &[Self], b: &[Self]) -> Vec<Self> {
+        return crate::multiply::karatsuba(a, b)
+    }
+}
+
+macro_rules! impl_integral_domain {
+    ($($t:ty),*) => {
+        $(impl IntegralDomain for $t {})*
+    };
+}
+
+impl_integral_domain!(i8, i16, i32, i64, i128, isize, BigInt);
+
+/// Floats only approximate the reals, but they are treated as a field so they can be used as
+/// coefficients.
+impl IntegralDomain for f32 {}
+impl Field for f32 {}
+impl IntegralDomain for f64 {}
+impl Field for f64 {}
+
+impl IntegralDomain for BigRational {}
+impl Field for BigRational {}
@@ -0,0 +1,307 @@
+//! Arithmetic circuits, which represent polynomials that are too large to expand.
+
+use rand::prelude::*;
+use num::{Zero, One};
+use std::ops::{Add, Sub, Mul};
+use crate::{IdentityTest, MultivariatePolynomial, Verdict, pit};
+use crate::field::FieldInfo;
+use crate::sample::{RandomFieldElement, SampleSet};
+
+/// GateId refers to a gate in a Circuit. Gates can only refer to gates that were added before
+/// them, so every circuit is a DAG.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct GateId(usize);
+
+/// Gate is a single node of an arithmetic circuit.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Gate<T> {
+    /// The variable x_i.
+    Input(usize),
+    /// A constant.
+    Const(T),
+    /// The sum of two earlier gates.
+    Add(GateId, GateId),
+    /// The product of two earlier gates.
+    Mul(GateId, GateId),
+}
+
+/// Circuit is a straight-line program computing a polynomial from its inputs using addition and
+/// multiplication gates.
+///
+/// Gates are shared, so a circuit with n gates can compute a polynomial of degree 2^n with
+/// exponentially many terms, like (x + y)^64, while evaluation still takes n operations.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Circuit<T> {
+    gates: Vec<Gate<T>>,
+    output: Option<GateId>,
+}
+
+impl<T> Circuit<T> {
+    /// Creates an empty circuit.
+    pub fn new() -> Self {
+        return Self { gates: vec![], output: None }
+    }
+
+    /// Returns the gates of the circuit in the order they were added.
+    pub fn gates(&self) -> &[Gate<T>] {
+        return &self.gates
+    }
+
+    /// Returns the output gate of the circuit, if one was set.
+    pub fn output(&self) -> Option<GateId> {
+        return self.output
+    }
+
+    /// Makes gate the output of the circuit, which is the polynomial the circuit represents.
+    pub fn set_output(&mut self, gate: GateId) {
+        self.check(gate);
+        self.output = Some(gate);
+    }
+
+    /// Adds the variable x_i.
+    pub fn input(&mut self, i: usize) -> GateId {
+        return self.push(Gate::Input(i))
+    }
+
+    /// Adds a constant.
+    pub fn constant(&mut self, c: T) -> GateId {
+        return self.push(Gate::Const(c))
+    }
+
+    /// Adds a gate computing a + b.
+    pub fn add(&mut self, a: GateId, b: GateId) -> GateId {
+        self.check(a);
+        self.check(b);
+        return self.push(Gate::Add(a, b))
+    }
+
+    /// Adds a gate computing a * b.
+    pub fn mul(&mut self, a: GateId, b: GateId) -> GateId {
+        self.check(a);
+        self.check(b);
+        return self.push(Gate::Mul(a, b))
+    }
+
+    /// Adds gates computing a - b, as a + (-1) * b.
+    pub fn sub(&mut self, a: GateId, b: GateId) -> GateId where
+        T: Zero + One + Sub<Output=T> {
+        let minus_one = self.constant(T::zero() - T::one());
+        let negated = self.mul(minus_one, b);
+        return self.add(a, negated)
+    }
+
+    /// Adds gates computing a^exp using repeated squaring, which takes O(log exp) gates.
+    pub fn pow(&mut self, a: GateId, mut exp: u64) -> GateId where
+        T: One {
+        let mut result = self.constant(T::one());
+        let mut square = a;
+        while exp > 0 {
+            if exp & 1 == 1 {
+                result = self.mul(result, square);
+            }
+            exp >>= 1;
+            if exp > 0 {
+                square = self.mul(square, square);
+            }
+        }
+        return result
+    }
+
+    /// Returns the formal degree of a gate: inputs have degree 1, constants degree 0, sums the
+    /// larger degree of their operands and products the sum of the degrees of their operands.
+    ///
+    /// This is an upper bound on the degree of the polynomial the gate computes, since
+    /// cancellations are not taken into account.
+    pub fn formal_degree(&self, gate: GateId) -> usize {
+        return self.formal_degrees()[gate.0]
+    }
+
+    fn formal_degrees(&self) -> Vec<usize> {
+        let mut degrees: Vec<usize> = Vec::with_capacity(self.gates.len());
+        for gate in self.gates.iter() {
+            let degree = match gate {
+                Gate::Input(_) => 1,
+                Gate::Const(_) => 0,
+                Gate::Add(a, b) => degrees[a.0].max(degrees[b.0]),
+                Gate::Mul(a, b) => degrees[a.0].saturating_add(degrees[b.0]),
+            };
+            degrees.push(degree);
+        }
+        return degrees
+    }
+
+    fn push(&mut self, gate: Gate<T>) -> GateId {
+        self.gates.push(gate);
+        return GateId(self.gates.len() - 1)
+    }
+
+    fn check(&self, gate: GateId) {
+        assert!(gate.0 < self.gates.len(), "gate {} is not part of this circuit", gate.0);
+    }
+}
+
+impl<T> Default for Circuit<T> {
+    fn default() -> Self {
+        return Self::new()
+    }
+}
+
+impl<T: Add<Output=T> + Mul<Output=T> + Zero + Clone> Circuit<T> {
+    /// Tests whether the circuit computes the zero polynomial without expanding it, by evaluating
+    /// it at points whose coordinates are drawn uniformly from sample_set.
+    ///
+    /// The formal degree d bounds the error of each trial by d/|sample_set|, and the number of
+    /// trials is picked so that a nonzero circuit is reported as zero with probability at most
+    /// error. Panics if d is not smaller than the size of sample_set, since then no number of
+    /// trials gives a bound.
+    pub fn is_zero_on<S: SampleSet<T> + ?Sized>(&self, sample_set: &S, error: f64) -> bool {
+        return self.is_zero_on_with_rng(sample_set, error, &mut rand::thread_rng())
+    }
+
+    /// Runs the same test as `is_zero_on`, drawing the random points from rng.
+    pub fn is_zero_on_with_rng<S: SampleSet<T> + ?Sized, R: Rng + ?Sized>(&self, sample_set: &S, error: f64, rng: &mut R) -> bool {
+        return pit::test_zero(self, sample_set, error, rng)
+            .unwrap_or_else(|| panic!("a sample set of size {} is too small for degree {}", sample_set.size(), self.total_degree()))
+            .is_probably_zero()
+    }
+}
+
+impl<T: Add<Output=T> + Mul<Output=T> + Clone> MultivariatePolynomial<T> for Circuit<T> {
+    /// The number of variables is one more than the highest input index.
+    fn variables(&self) -> usize {
+        return self.gates.iter().filter_map(|gate| match gate {
+            Gate::Input(i) => Some(i + 1),
+            _ => None,
+        }).max().unwrap_or(0)
+    }
+
+    /// Returns the formal degree of the output gate.
+    fn total_degree(&self) -> usize {
+        return self.output.map_or(0, |output| self.formal_degree(output))
+    }
+
+    /// Evaluates every gate in order and returns the value of the output gate, or None if no
+    /// output was set.
+    fn evaluate_at(&self, point: &[T]) -> Option<T> {
+        let output = self.output?;
+        let mut values: Vec<T> = Vec::with_capacity(output.0 + 1);
+        for gate in self.gates[..=output.0].iter() {
+            let value = match gate {
+                Gate::Input(i) => point[*i].clone(),
+                Gate::Const(c) => c.clone(),
+                Gate::Add(a, b) => values[a.0].clone() + values[b.0].clone(),
+                Gate::Mul(a, b) => values[a.0].clone() * values[b.0].clone(),
+            };
+            values.push(value);
+        }
+        return values.pop()
+    }
+}
+
+/// Tests the circuit over its field of constants using its formal degree, see
+/// `pit::test_zero_over_field`.
+impl<T> IdentityTest<T> for Circuit<T> where
+    T: Add<Output=T> + Mul<Output=T> + Zero + Clone + FieldInfo + RandomFieldElement {
+    fn try_test_zero_with_rng<R: Rng + ?Sized>(&self, error: f64, rng: &mut R) -> Option<Verdict<T>> {
+        return pit::test_zero_over_field(self, error, rng)
+    }
+}
+
+/// Builds the circuit (x + y)^64 - sum_k c_k x^k y^(64 - k), where c_k is the binomial
+/// coefficient C(64, k) plus error_at_k if k is the given index.
+#[cfg(test)]
+fn binomial_difference(error_at: Option<usize>) -> Circuit<crate::field::Fp<2147483647>> {
+    use crate::field::Fp;
+
+    let mut circuit = Circuit::new();
+    let x = circuit.input(0);
+    let y = circuit.input(1);
+    let sum = circuit.add(x, y);
+    let power = circuit.pow(sum, 64);
+
+    let mut row = vec![Fp::<2147483647>::one()];
+    for _ in 0..64 {
+        let mut next = vec![Fp::one(); row.len() + 1];
+        for k in 1..row.len() {
+            next[k] = row[k - 1] + row[k];
+        }
+        row = next;
+    }
+    let mut expanded = circuit.constant(Fp::zero());
+    for (k, binomial) in row.into_iter().enumerate() {
+        let coefficient = if error_at == Some(k) { binomial + Fp::one() } else { binomial };
+        let c = circuit.constant(coefficient);
+        let x_k = circuit.pow(x, k as u64);
+        let y_k = circuit.pow(y, 64 - k as u64);
+        let monomial = circuit.mul(x_k, y_k);
+        let term = circuit.mul(c, monomial);
+        expanded = circuit.add(expanded, term);
+    }
+    let difference = circuit.sub(power, expanded);
+    circuit.set_output(difference);
+    return circuit
+}
+
+#[test]
+fn circuit_formal_degree_and_evaluation() {
+    let mut circuit = Circuit::<i64>::new();
+    let x = circuit.input(0);
+    let y = circuit.input(2);
+    let three = circuit.constant(3);
+    let product = circuit.mul(x, y);
+    let scaled = circuit.mul(three, product);
+    let cube = circuit.pow(x, 3);
+    let sum = circuit.add(scaled, cube);
+    circuit.set_output(sum);
+    assert_eq!(circuit.formal_degree(three), 0);
+    assert_eq!(circuit.formal_degree(scaled), 2);
+    assert_eq!(circuit.total_degree(), 3);
+    assert_eq!(circuit.variables(), 3);
+    // 3xz + x^3 at (2, 100, 5)
+    assert_eq!(circuit.evaluate_at(&[2, 100, 5]), Some(38));
+    assert_eq!(Circuit::<i64>::new().evaluate_at(&[]), None);
+}
+
+#[test]
+fn circuit_formal_degree_ignores_cancellation() {
+    let mut circuit = Circuit::<i64>::new();
+    let x = circuit.input(0);
+    let square = circuit.mul(x, x);
+    let difference = circuit.sub(square, square);
+    circuit.set_output(difference);
+    assert_eq!(circuit.total_degree(), 2);
+    assert_eq!(circuit.evaluate_at(&[7]), Some(0));
+}
+
+#[test]
+fn circuit_binomial_identity_without_expanding() {
+    use crate::field::Fp;
+
+    let sample_set: Vec<Fp<2147483647>> = (0..10000).map(Fp::new).collect();
+    let identity = binomial_difference(None);
+    assert_eq!(identity.total_degree(), 64);
+    assert_eq!(identity.is_zero_on(&sample_set, 1e-9), true);
+    let wrong = binomial_difference(Some(17));
+    assert_eq!(wrong.is_zero_on(&sample_set, 1e-9), false);
+
+    // over the whole field the bound is exact, and a nonzero verdict comes with a witness
+    let mut rng = StdRng::seed_from_u64(3);
+    match identity.test_zero_with_rng(1e-9, &mut rng) {
+        Verdict::ProbablyZero { trials, error_bound } => {
+            assert_eq!(trials, 2);
+            assert!(error_bound <= 1e-9);
+        }
+        verdict => panic!("unexpected verdict {:?}", verdict),
+    }
+    let witness = wrong.test_zero_with_rng(1e-9, &mut rng).witness().unwrap().to_vec();
+    assert_ne!(wrong.evaluate_at(&witness), Some(Fp::zero()));
+}
+
+#[test]
+#[should_panic]
+fn circuit_sample_set_too_small() {
+    use crate::field::Fp;
+
+    let sample_set: Vec<Fp<2147483647>> = (0..64).map(Fp::new).collect();
+    binomial_difference(None).is_zero_on(&sample_set, 0.01);
+}
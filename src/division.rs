@@ -0,0 +1,413 @@
+//! Euclidean division and greatest common divisors for VecPoly over a field, and remainders
+//! modulo monic polynomials over any commutative ring.
+
+use num::{BigUint, One};
+use std::ops::{Div, Rem};
+use crate::VecPoly;
+use crate::algebra::{Field, Ring};
+
+/// The length of the divisor and the quotient below which `div_rem_fast` uses long division,
+/// whose lower overhead wins for short inputs.
+const FAST_DIVISION_THRESHOLD: usize = 64;
+
+impl<T> VecPoly<T> where
+    T: Field {
+    /// Returns the quotient q and remainder r of dividing by divisor, so that
+    /// self = q * divisor + r where r has a smaller degree than divisor.
+    ///
+    /// Panics if divisor is the zero polynomial.
+    pub fn div_rem(&self, divisor: &Self) -> (Self, Self) {
+        let divisor_degree = divisor.degree().expect("division by the zero polynomial");
+        let lead = &divisor.coefficients[divisor_degree];
+        if self.coefficients.len() <= divisor_degree {
+            return (Self::from_lowest_first(vec![]), self.clone())
+        }
+        let mut remainder = self.coefficients.clone();
+        let mut quotient = vec![T::zero(); remainder.len() - divisor_degree];
+        for shift in (0..quotient.len()).rev() {
+            let factor = remainder[shift + divisor_degree].clone() / lead.clone();
+            for (i, coef) in divisor.coefficients.iter().enumerate() {
+                remainder[shift + i] = std::mem::replace(&mut remainder[shift + i], T::zero()) - factor.clone() * coef.clone();
+            }
+            quotient[shift] = factor;
+        }
+        // the terms of degree at least deg(divisor) cancel exactly, so drop them even if rounding
+        // left something behind
+        remainder.truncate(divisor_degree);
+        return (Self::from_lowest_first(quotient), Self::from_lowest_first(remainder))
+    }
+
+    /// Returns the quotient and remainder like `div_rem`, in a constant number of products
+    /// through `mul_fast` instead of O(nm) operations.
+    ///
+    /// Reversing the coefficients turns division into multiplication by a power series: with
+    /// k = deg(self) - deg(divisor) + 1, the reversed quotient is rev(self) / rev(divisor) mod
+    /// x^k, and rev(divisor) has the leading coefficient as its constant term, so it is
+    /// invertible. Panics if divisor is the zero polynomial.
+    pub fn div_rem_fast(&self, divisor: &Self) -> (Self, Self) {
+        let divisor_degree = divisor.degree().expect("division by the zero polynomial");
+        let quotient_len = self.coefficients.len().saturating_sub(divisor_degree);
+        if divisor_degree < FAST_DIVISION_THRESHOLD || quotient_len < FAST_DIVISION_THRESHOLD {
+            return self.div_rem(divisor)
+        }
+        let reversed = |poly: &Self| Self::from_lowest_first(poly.coefficients.iter().rev().cloned().collect());
+        let reversed_quotient = reversed(self).truncated(quotient_len).mul_fast(&reversed(divisor).inverse_series(quotient_len)).truncated(quotient_len);
+        // the reversed quotient can end in zeros, which are leading zeros of neither polynomial
+        let mut coefficients = reversed_quotient.coefficients.clone();
+        coefficients.resize(quotient_len, T::zero());
+        coefficients.reverse();
+        let quotient = Self::from_lowest_first(coefficients);
+        let remainder = (self - &quotient.mul_fast(divisor)).truncated(divisor_degree);
+        return (quotient, remainder)
+    }
+
+    /// Returns the first n coefficients of the power series 1 / self, which needs a nonzero
+    /// constant term. Each step of the Newton iteration g -> g (2 - self g) doubles the number of
+    /// correct coefficients, so this takes O(M(n)) operations.
+    fn inverse_series(&self, n: usize) -> Self {
+        let mut inverse = Self::from_lowest_first(vec![T::one() / self.coefficients[0].clone()]);
+        let mut len = 1;
+        while len < n {
+            len = (2 * len).min(n);
+            // 2 - self g, where self g = 1 + O(x^(len/2))
+            let product = self.truncated(len).mul_fast(&inverse).truncated(len);
+            let mut correction: Vec<T> = product.coefficients.iter().map(|c| T::zero() - c.clone()).collect();
+            if correction.is_empty() {
+                correction.push(T::zero());
+            }
+            correction[0] = correction[0].clone() + T::one() + T::one();
+            inverse = inverse.mul_fast(&Self::from_lowest_first(correction)).truncated(len);
+        }
+        return inverse
+    }
+
+    /// Returns the polynomial modulo x^n, keeping the first n coefficients.
+    fn truncated(&self, n: usize) -> Self {
+        return Self::from_lowest_first(self.coefficients.iter().take(n).cloned().collect())
+    }
+
+    /// Returns the polynomial scaled so that its leading coefficient is one. The zero polynomial
+    /// is returned unchanged.
+    pub fn monic(&self) -> Self {
+        match self.leading_coefficient() {
+            Some(lead) => return self / lead.clone(),
+            None => return self.clone(),
+        }
+    }
+
+    /// Returns the monic greatest common divisor of the two polynomials, or the zero polynomial
+    /// if both are zero.
+    pub fn gcd(&self, other: &Self) -> Self {
+        let (mut a, mut b) = (self.clone(), other.clone());
+        while b.degree().is_some() {
+            let (_, remainder) = a.div_rem(&b);
+            a = std::mem::replace(&mut b, remainder);
+        }
+        return a.monic()
+    }
+
+    /// Returns self^exp mod modulus by repeated squaring, which takes O(log exp) products of
+    /// polynomials of degree less than that of modulus. Exponents like q^d for a field with q
+    /// elements don't fit in a machine integer, so exp is a BigUint. The products go through
+    /// `mul_fast`.
+    pub fn pow_mod(&self, exp: &BigUint, modulus: &Self) -> Self {
+        return self.pow_reduce(exp, |a, b| a.mul_fast(b), |poly| poly % modulus)
+    }
+
+    /// Runs the extended Euclidean algorithm, returning (g, s, t) where g is the monic greatest
+    /// common divisor and s * self + t * other = g.
+    ///
+    /// When both polynomials are zero, all three are zero.
+    pub fn extended_gcd(&self, other: &Self) -> (Self, Self, Self) {
+        let (mut r0, mut r1) = (self.clone(), other.clone());
+        let (mut s0, mut s1) = (Self::one(), Self::from_lowest_first(vec![]));
+        let (mut t0, mut t1) = (Self::from_lowest_first(vec![]), Self::one());
+        while r1.degree().is_some() {
+            let (quotient, remainder) = r0.div_rem(&r1);
+            let s2 = &s0 - &(&quotient * &s1);
+            let t2 = &t0 - &(&quotient * &t1);
+            r0 = std::mem::replace(&mut r1, remainder);
+            s0 = std::mem::replace(&mut s1, s2);
+            t0 = std::mem::replace(&mut t1, t2);
+        }
+        match r0.leading_coefficient() {
+            Some(lead) => {
+                let lead = lead.clone();
+                return (&r0 / lead.clone(), &s0 / lead.clone(), &t0 / lead)
+            }
+            None => return (r0, Self::from_lowest_first(vec![]), Self::from_lowest_first(vec![])),
+        }
+    }
+}
+
+impl<T> VecPoly<T> where
+    T: Ring {
+    /// Returns self^exp by repeated squaring with the given product, applying reduce to the base
+    /// and every product.
+    pub(crate) fn pow_reduce<M, F>(&self, exp: &BigUint, multiply: M, reduce: F) -> Self where
+        M: Fn(&Self, &Self) -> Self,
+        F: Fn(&Self) -> Self {
+        let mut result = reduce(&Self::one());
+        let mut square = reduce(self);
+        let bits = exp.bits();
+        for (i, digit) in exp.to_u32_digits().into_iter().enumerate() {
+            for bit in 0..32 {
+                if (digit >> bit) & 1 == 1 {
+                    result = reduce(&multiply(&result, &square));
+                }
+                if (32 * i + bit + 1) as u64 >= bits {
+                    break
+                }
+                square = reduce(&multiply(&square, &square));
+            }
+        }
+        return result
+    }
+}
+
+impl<T> VecPoly<T> where
+    T: Ring + PartialEq {
+    /// Returns the remainder of dividing by a monic divisor. Dividing by a leading coefficient of
+    /// one needs no inverses, so this works over any commutative ring, like the integers mod a
+    /// composite n where `div_rem` isn't available.
+    ///
+    /// Panics if divisor is not monic.
+    pub fn rem_monic(&self, divisor: &Self) -> Self {
+        let divisor_degree = divisor.degree().expect("division by the zero polynomial");
+        assert!(divisor.coefficients[divisor_degree].is_one(), "the divisor must be monic");
+        let mut remainder = self.coefficients.clone();
+        for shift in (0..remainder.len().saturating_sub(divisor_degree)).rev() {
+            let factor = std::mem::replace(&mut remainder[shift + divisor_degree], T::zero());
+            if factor.is_zero() {
+                continue
+            }
+            for (i, coef) in divisor.coefficients[..divisor_degree].iter().enumerate() {
+                remainder[shift + i] = std::mem::replace(&mut remainder[shift + i], T::zero()) - factor.clone() * coef.clone();
+            }
+        }
+        remainder.truncate(divisor_degree);
+        return Self::from_lowest_first(remainder)
+    }
+
+    /// Returns self^exp mod the monic modulus by repeated squaring, like `pow_mod` but over any
+    /// commutative ring. Panics if modulus is not monic.
+    pub fn pow_mod_monic(&self, exp: &BigUint, modulus: &Self) -> Self {
+        return self.pow_reduce(exp, |a, b| a * b, |poly| poly.rem_monic(modulus))
+    }
+}
+
+impl<'a, T> Div<&'a VecPoly<T>> for &'a VecPoly<T> where
+    T: Field {
+    type Output=VecPoly<T>;
+
+    /// Returns the quotient of Euclidean division. Panics if other is the zero polynomial.
+    fn div(self, other: &'a VecPoly<T>) -> VecPoly<T> {
+        return self.div_rem(other).0
+    }
+}
+
+impl<T> Div for VecPoly<T> where
+    T: Field {
+    type Output=Self;
+
+    fn div(self, other: Self) -> Self {
+        return &self / &other
+    }
+}
+
+impl<'a, T> Rem<&'a VecPoly<T>> for &'a VecPoly<T> where
+    T: Field {
+    type Output=VecPoly<T>;
+
+    /// Returns the remainder of Euclidean division. Panics if other is the zero polynomial.
+    fn rem(self, other: &'a VecPoly<T>) -> VecPoly<T> {
+        return self.div_rem(other).1
+    }
+}
+
+impl<T> Rem for VecPoly<T> where
+    T: Field {
+    type Output=Self;
+
+    fn rem(self, other: Self) -> Self {
+        return &self % &other
+    }
+}
+
+#[cfg(test)]
+fn random_poly<R: rand::Rng>(degree: usize, rng: &mut R) -> VecPoly<crate::field::Fp<65537>> {
+    use crate::field::Fp;
+
+    // force a nonzero leading coefficient so the degree is exact
+    let mut coefficients: Vec<Fp<65537>> = (0..degree).map(|_| rng.gen()).collect();
+    coefficients.push(Fp::new(rng.gen_range(1, 65537)));
+    return VecPoly::from_lowest_first(coefficients)
+}
+
+#[test]
+fn div_rem_small_example() {
+    // x^3 - 2x^2 - 4 = (x - 3)(x^2 + x + 3) + 5
+    let dividend = VecPoly::new(vec![1.0, -2.0, 0.0, -4.0]);
+    let divisor = VecPoly::new(vec![1.0, -3.0]);
+    let (quotient, remainder) = dividend.div_rem(&divisor);
+    assert_eq!(quotient.coefficients(), &[3.0, 1.0, 1.0]);
+    assert_eq!(remainder.coefficients(), &[5.0]);
+    assert_eq!((&dividend / &divisor).coefficients(), &[3.0, 1.0, 1.0]);
+    assert_eq!((&dividend % &divisor).coefficients(), &[5.0]);
+
+    // dividing by a polynomial of larger degree leaves everything in the remainder
+    let (quotient, remainder) = divisor.div_rem(&dividend);
+    assert_eq!(quotient.degree(), None);
+    assert_eq!(remainder.coefficients(), divisor.coefficients());
+}
+
+#[test]
+fn div_rem_reconstructs_dividend() {
+    use rand::prelude::*;
+
+    let mut rng = StdRng::seed_from_u64(7);
+    for (a_degree, b_degree) in [(0, 0), (3, 5), (5, 3), (8, 1), (10, 10)].iter() {
+        let a = random_poly(*a_degree, &mut rng);
+        let b = random_poly(*b_degree, &mut rng);
+        let (quotient, remainder) = a.div_rem(&b);
+        assert!(remainder.degree().is_none_or(|d| d < *b_degree));
+        let reconstructed = &(&quotient * &b) + &remainder;
+        assert_eq!(reconstructed.coefficients(), a.coefficients());
+        assert!(reconstructed == a);
+    }
+}
+
+#[test]
+#[should_panic]
+fn div_rem_by_zero() {
+    let _ = VecPoly::new(vec![1.0, 2.0]).div_rem(&VecPoly::new(vec![]));
+}
+
+#[test]
+fn monic_and_gcd_of_known_factors() {
+    use crate::field::Fp;
+
+    let f = |c: i64| Fp::<101>::from_i64(c);
+    // 3(x - 1)(x - 2) and 5(x - 1)(x - 3)
+    let a = VecPoly::new(vec![f(3), f(-9), f(6)]);
+    let b = VecPoly::new(vec![f(5), f(-20), f(15)]);
+    assert_eq!(a.monic().coefficients(), &[f(2), f(-3), f(1)]);
+    assert_eq!(a.gcd(&b).coefficients(), &[f(-1), f(1)]);
+    assert_eq!(a.gcd(&VecPoly::new(vec![])).coefficients(), a.monic().coefficients());
+    assert_eq!(VecPoly::<Fp<101>>::new(vec![]).gcd(&VecPoly::new(vec![])).degree(), None);
+    // coprime polynomials have gcd 1
+    assert_eq!(a.gcd(&VecPoly::new(vec![f(1), f(0), f(1)])).coefficients(), &[f(1)]);
+}
+
+#[test]
+fn random_gcd_identities() {
+    use num::Zero;
+    use rand::prelude::*;
+    use crate::IdentityTest;
+
+    let mut rng = StdRng::seed_from_u64(11);
+    for _ in 0..10 {
+        let a = random_poly(rng.gen_range(0, 8), &mut rng);
+        let b = random_poly(rng.gen_range(0, 8), &mut rng);
+        let c = random_poly(rng.gen_range(1, 5), &mut rng);
+        let ac = &a * &c;
+        let bc = &b * &c;
+        let (g, s, t) = ac.extended_gcd(&bc);
+        // the Bezout identity s * ac + t * bc - g = 0, checked with the identity test and then
+        // exactly
+        let bezout = &(&(&s * &ac) + &(&t * &bc)) - &g;
+        assert!(bezout.test_zero_with_rng(1e-9, &mut rng).is_probably_zero());
+        assert!(bezout.is_zero());
+        // c divides the gcd, and the gcd divides both inputs
+        assert!((&g % &c).is_zero());
+        assert!((&ac % &g).is_zero());
+        assert!((&bc % &g).is_zero());
+        assert!(g == a.gcd(&b).monic() * c.monic());
+        assert!(g == bc.gcd(&ac));
+    }
+}
+
+#[test]
+fn rational_division_is_exact() {
+    use num::BigRational;
+
+    let q = |n: i64, d: i64| BigRational::new(n.into(), d.into());
+    // dividing (x - 0.1)(x - 0.2)(x - 0.7) by x - 0.1 doesn't give back (x - 0.2)(x - 0.7) in
+    // floats, but does with rationals
+    let float_factors = (VecPoly::new(vec![1.0, -0.1]), VecPoly::new(vec![1.0, -0.2]), VecPoly::new(vec![1.0, -0.7]));
+    let float_product = &(&float_factors.0 * &float_factors.1) * &float_factors.2;
+    let float_quotient = float_product.div_rem(&float_factors.0).0;
+    assert!(float_quotient != &float_factors.1 * &float_factors.2);
+    let (a, b, c) = (VecPoly::new(vec![q(1, 1), q(-1, 10)]), VecPoly::new(vec![q(1, 1), q(-2, 10)]), VecPoly::new(vec![q(1, 1), q(-7, 10)]));
+    let product = &(&a * &b) * &c;
+    let (quotient, remainder) = product.div_rem(&a);
+    assert_eq!(remainder.degree(), None);
+    assert!(quotient == &b * &c);
+
+    // the Bezout identity holds exactly, and the gcd is the common factor
+    let (g, s, t) = (&a * &b).extended_gcd(&(&a * &c));
+    assert!(g == a);
+    assert!(&(&s * &(&a * &b)) + &(&t * &(&a * &c)) == g);
+}
+
+#[test]
+fn pow_mod_matches_repeated_multiplication() {
+    use crate::field::Fp;
+    use rand::prelude::*;
+
+    let mut rng = StdRng::seed_from_u64(27);
+    let base = random_poly(6, &mut rng);
+    let modulus = random_poly(4, &mut rng);
+    let mut expected = &VecPoly::one() % &modulus;
+    for exp in 0u64..70 {
+        assert_eq!(base.pow_mod(&BigUint::from(exp), &modulus), expected);
+        expected = &(&expected * &base) % &modulus;
+    }
+    // x^p = x mod x^p - x for any exponent that is a power of p
+    let p = 7u64;
+    let mut coefficients = vec![Fp::<7>::new(0); p as usize + 1];
+    coefficients[1] = Fp::from_i64(-1);
+    coefficients[p as usize] = Fp::new(1);
+    let fermat = VecPoly::from_lowest_first(coefficients);
+    let x = VecPoly::new(vec![Fp::<7>::new(1), Fp::new(0)]);
+    assert_eq!(x.pow_mod(&num::pow(BigUint::from(p), 40), &fermat), x);
+}
+
+#[test]
+fn monic_remainders_over_a_ring() {
+    use rand::prelude::*;
+
+    let mut rng = StdRng::seed_from_u64(28);
+    for &(q_degree, m_degree) in [(0, 1), (3, 2), (5, 5), (1, 8)].iter() {
+        // q * m + r over the integers, which have no division
+        let mut random = |len: usize| VecPoly::from_lowest_first((0..len).map(|_| rng.gen_range(-50i64, 50)).collect());
+        let q = random(q_degree + 1);
+        let r = random(m_degree);
+        let mut m = random(m_degree);
+        m += VecPoly::from_lowest_first((0..=m_degree).map(|i| if i == m_degree { 1 } else { 0 }).collect());
+        assert_eq!((&(&q * &m) + &r).rem_monic(&m), r);
+    }
+    // over a field this agrees with pow_mod
+    let base = random_poly(6, &mut rng);
+    let modulus = random_poly(4, &mut rng).monic();
+    for exp in [0u64, 1, 2, 65536, 1 << 40].iter() {
+        assert_eq!(base.pow_mod_monic(&BigUint::from(*exp), &modulus), base.pow_mod(&BigUint::from(*exp), &modulus));
+    }
+}
+
+#[test]
+fn fast_division_matches_long_division() {
+    use crate::field::Fp;
+    use rand::prelude::*;
+
+    let mut rng = StdRng::seed_from_u64(29);
+    for &(a_degree, b_degree) in [(10, 3), (200, 64), (300, 100), (500, 450), (1000, 70), (100, 200)].iter() {
+        let a = random_poly(a_degree, &mut rng);
+        let b = random_poly(b_degree, &mut rng);
+        assert_eq!(a.div_rem_fast(&b), a.div_rem(&b), "{} / {}", a_degree, b_degree);
+    }
+    // without large roots of unity the Newton iteration multiplies with Karatsuba
+    let a = VecPoly::from_lowest_first((0..700).map(|_| rng.gen::<Fp<2147483647>>()).collect());
+    let b = VecPoly::from_lowest_first((0..300).map(|_| rng.gen::<Fp<2147483647>>()).collect());
+    assert_eq!(a.div_rem_fast(&b), a.div_rem(&b));
+}
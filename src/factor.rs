@@ -0,0 +1,293 @@
+//! Factorization of polynomials over finite fields.
+//!
+//! A polynomial over GF(q) is factored in three stages. Square-free factorization separates the
+//! irreducible factors by multiplicity using gcds with the derivative. Distinct-degree
+//! factorization separates the factors of a square-free polynomial by degree, since
+//! x^(q^i) - x is the product of all monic irreducible polynomials whose degree divides i.
+//! Cantor-Zassenhaus then splits a product of irreducible factors of equal degree d with gcds
+//! against random elements of GF(q)[x]/(f) raised to (q^d - 1)/2, which is a random square root
+//! of one modulo each factor, so every split succeeds with probability about 1/2.
+
+use rand::prelude::*;
+use num::{BigUint, One};
+use crate::VecPoly;
+use crate::algebra::Field;
+use crate::field::{Cardinality, FieldInfo};
+use crate::sample::{RandomFieldElement, SampleSet};
+
+/// Factorization is a polynomial written as unit * prod f_i^(m_i), where the f_i are distinct
+/// monic irreducible polynomials and unit is the leading coefficient.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Factorization<T> {
+    pub unit: T,
+    pub factors: Vec<(VecPoly<T>, usize)>,
+}
+
+impl<T: Field> Factorization<T> {
+    /// Multiplies the factors back together.
+    pub fn product(&self) -> VecPoly<T> {
+        let mut product = VecPoly::from_lowest_first(vec![self.unit.clone()]);
+        for (factor, multiplicity) in self.factors.iter() {
+            product *= factor.pow(*multiplicity as u64);
+        }
+        return product
+    }
+}
+
+/// Returns base^exp by repeated squaring.
+fn power<T: Field>(base: T, exp: &BigUint) -> T {
+    let mut result = T::one();
+    let mut square = base;
+    for digit in exp.to_u32_digits() {
+        for bit in 0..32 {
+            if (digit >> bit) & 1 == 1 {
+                result = result * square.clone();
+            }
+            square = square.clone() * square;
+        }
+    }
+    return result
+}
+
+impl<T> VecPoly<T> where
+    T: Field + PartialEq + FieldInfo + RandomFieldElement {
+    /// Returns the characteristic p and the size q = p^k of the field. Panics if it is infinite.
+    pub(crate) fn field_size() -> (u64, u32) {
+        match T::cardinality() {
+            Cardinality::Finite { characteristic, degree } => return (characteristic, degree),
+            Cardinality::Infinite => panic!("factorization needs a finite field"),
+        }
+    }
+
+    /// Returns the polynomial x.
+    pub(crate) fn x() -> Self {
+        return VecPoly::from_lowest_first(vec![T::zero(), T::one()])
+    }
+
+    /// Splits the monic polynomial into pairs (g, m) where g is the square-free product of the
+    /// irreducible factors with multiplicity m. Panics if the polynomial is zero or not monic.
+    ///
+    /// gcd(f, f') removes one copy of every factor whose multiplicity isn't divisible by p, and
+    /// repeated gcds peel off one multiplicity at a time. What remains has a zero derivative, so
+    /// it is a polynomial in x^p, whose p-th root is factored recursively.
+    pub fn square_free_factorization(&self) -> Vec<(Self, usize)> {
+        assert!(self.leading_coefficient().is_some_and(|c| c.is_one()), "the polynomial must be monic");
+        let (p, k) = Self::field_size();
+        let mut factors = vec![];
+        let mut c = self.gcd(&self.derivative());
+        let mut w = self / &c;
+        let mut i = 1;
+        while w.degree() != Some(0) {
+            let y = w.gcd(&c);
+            let factor = &w / &y;
+            if factor.degree() != Some(0) {
+                factors.push((factor, i));
+            }
+            c = &c / &y;
+            w = y;
+            i += 1;
+        }
+        if c.degree() != Some(0) {
+            // c(x) = sum c_i x^(ip), so its p-th root is sum c_i^(q/p) x^i
+            let root_exp = num::pow(BigUint::from(p), k as usize - 1);
+            let root = VecPoly::from_lowest_first(c.coefficients.iter().step_by(p as usize).map(|a| power(a.clone(), &root_exp)).collect());
+            for (factor, multiplicity) in root.square_free_factorization() {
+                factors.push((factor, multiplicity * p as usize));
+            }
+        }
+        return factors
+    }
+
+    /// Splits the monic square-free polynomial into pairs (g, d) where g is the product of its
+    /// irreducible factors of degree d. The gcd of f with x^(q^d) - x picks out the factors of
+    /// degree d once the ones of smaller degree are removed.
+    pub fn distinct_degree_factorization(&self) -> Vec<(Self, usize)> {
+        let (p, k) = Self::field_size();
+        let q = num::pow(BigUint::from(p), k as usize);
+        let mut factors = vec![];
+        let mut rest = self.clone();
+        let mut h = &Self::x() % &rest;
+        let mut d = 1;
+        while rest.degree().is_some_and(|n| n >= 2 * d) {
+            h = h.pow_mod(&q, &rest);
+            let g = rest.gcd(&(&h - &Self::x()));
+            if g.degree() != Some(0) {
+                rest = &rest / &g;
+                h = &h % &rest;
+                factors.push((g, d));
+            }
+            d += 1;
+        }
+        if let Some(n) = rest.degree().filter(|&n| n > 0) {
+            factors.push((rest, n));
+        }
+        return factors
+    }
+
+    /// Splits the monic square-free polynomial, all of whose irreducible factors have the given
+    /// degree, into those factors with the Cantor-Zassenhaus algorithm, drawing random
+    /// polynomials from rng.
+    ///
+    /// In odd characteristic a random a is a square modulo each factor independently with
+    /// probability about 1/2, so gcd(a^((q^d - 1)/2) - 1, f) is a nontrivial factor with
+    /// probability about 1/2. In characteristic 2 the trace a + a^2 + ... + a^(2^(kd-1)) plays the
+    /// same role, being 0 or 1 modulo each factor.
+    pub fn equal_degree_factorization_with_rng<R: Rng + ?Sized>(&self, degree: usize, rng: &mut R) -> Vec<Self> {
+        let n = self.degree().expect("the zero polynomial has no factorization");
+        assert!(degree > 0 && n.is_multiple_of(degree), "a polynomial of degree {} is not a product of factors of degree {}", n, degree);
+        if n == degree {
+            return vec![self.clone()]
+        }
+        let (p, k) = Self::field_size();
+        let sample_set = T::sample_set();
+        let exponent = (num::pow(BigUint::from(p), k as usize * degree) - BigUint::one()) / BigUint::from(2u32);
+        loop {
+            let a = VecPoly::from_lowest_first((0..n).map(|_| sample_set.sample(rng)).collect());
+            if a.degree().is_none_or(|d| d == 0) {
+                continue
+            }
+            let b = if p == 2 {
+                let mut square = a.clone();
+                let mut trace = a.clone();
+                for _ in 1..k as usize * degree {
+                    square = &(&square * &square) % self;
+                    trace = &trace + &square;
+                }
+                trace
+            } else {
+                &a.pow_mod(&exponent, self) - &Self::one()
+            };
+            // a itself may already share a factor with self
+            for candidate in [a, b].iter() {
+                let g = self.gcd(candidate);
+                if g.degree().is_some_and(|d| 0 < d && d < n) {
+                    let mut factors = g.equal_degree_factorization_with_rng(degree, rng);
+                    factors.extend((self / &g).equal_degree_factorization_with_rng(degree, rng));
+                    return factors
+                }
+            }
+        }
+    }
+
+    /// Factors the polynomial into its leading coefficient and monic irreducible factors with
+    /// multiplicities, drawing the randomness of Cantor-Zassenhaus from rng. The factors are
+    /// sorted by degree and then multiplicity. Panics if the polynomial is zero.
+    pub fn factor_with_rng<R: Rng + ?Sized>(&self, rng: &mut R) -> Factorization<T> {
+        let unit = self.leading_coefficient().expect("the zero polynomial has no factorization").clone();
+        let mut factors = vec![];
+        for (part, multiplicity) in self.monic().square_free_factorization() {
+            for (same_degree, degree) in part.distinct_degree_factorization() {
+                for factor in same_degree.equal_degree_factorization_with_rng(degree, rng) {
+                    factors.push((factor, multiplicity));
+                }
+            }
+        }
+        factors.sort_by_key(|(factor, multiplicity)| (factor.degree(), *multiplicity));
+        return Factorization { unit, factors }
+    }
+
+    /// Runs `factor_with_rng` with thread_rng.
+    pub fn factor(&self) -> Factorization<T> {
+        return self.factor_with_rng(&mut rand::thread_rng())
+    }
+}
+
+#[cfg(test)]
+fn random_monic<T: Field + RandomFieldElement, R: Rng>(degree: usize, rng: &mut R) -> VecPoly<T> {
+    let mut coefficients: Vec<T> = (0..degree).map(|_| T::sample_set().sample(rng)).collect();
+    coefficients.push(T::one());
+    return VecPoly::from_lowest_first(coefficients)
+}
+
+/// Checks that the factors multiply back to poly, and that each is monic and passes the
+/// distinct-degree test for irreducibility.
+#[cfg(test)]
+fn check_factorization<T>(poly: &VecPoly<T>, factorization: &Factorization<T>) where
+    T: Field + PartialEq + FieldInfo + RandomFieldElement + std::fmt::Debug {
+    assert_eq!(&factorization.product(), poly);
+    for (factor, _) in factorization.factors.iter() {
+        assert!(factor.leading_coefficient().is_some_and(|c| c.is_one()));
+        assert_eq!(factor.distinct_degree_factorization(), vec![(factor.clone(), factor.degree().unwrap())]);
+    }
+}
+
+#[test]
+fn factors_with_known_multiplicities_over_gf3() {
+    use crate::field::{Ext, ExtensionModulus, Fp};
+
+    /// x^5 + 2x + 1, which is irreducible over GF(3).
+    struct GF243Modulus;
+
+    impl ExtensionModulus<Fp<3>> for GF243Modulus {
+        fn modulus() -> VecPoly<Fp<3>> {
+            return VecPoly::new(vec![Fp::new(1), Fp::new(0), Fp::new(0), Fp::new(0), Fp::new(2), Fp::new(1)])
+        }
+    }
+
+    let mut rng = StdRng::seed_from_u64(28);
+    let f = |c: u64| Fp::<3>::new(c);
+    let x = VecPoly::new(vec![f(1), f(0)]);
+    let x_plus_one = VecPoly::new(vec![f(1), f(1)]);
+    let x_squared_plus_one = VecPoly::new(vec![f(1), f(0), f(1)]);
+    // 2 x^3 (x + 1)^4 (x^2 + 1)^6, where the multiplicities 3 and 6 need p-th roots
+    let poly = &(&(&x.pow(3) * &x_plus_one.pow(4)) * &x_squared_plus_one.pow(6)) * f(2);
+    let factorization = poly.factor_with_rng(&mut rng);
+    assert_eq!(factorization.unit, f(2));
+    assert_eq!(factorization.factors, vec![(x, 3), (x_plus_one, 4), (x_squared_plus_one, 6)]);
+    check_factorization(&poly, &factorization);
+    // the degree is too large for GF(3) to identity test, so the check runs in GF(3^5)
+    let difference = &factorization.product() - &poly;
+    assert!(difference.test_zero_lifted_with_rng::<Ext<Fp<3>, GF243Modulus, 5>, _>(1e-9, &mut rng).is_some_and(|v| v.is_probably_zero()));
+}
+
+#[test]
+fn random_polynomials_factor_over_prime_and_binary_fields() {
+    use crate::ProbablyEq;
+    use crate::field::{Fp, GF256};
+
+    let mut rng = StdRng::seed_from_u64(29);
+    for degree in [1, 2, 7, 20].iter() {
+        let poly: VecPoly<Fp<65537>> = random_monic(*degree, &mut rng);
+        let squared = &poly * &poly;
+        let factorization = squared.factor_with_rng(&mut rng);
+        check_factorization(&squared, &factorization);
+        assert!(factorization.factors.iter().all(|(_, m)| m % 2 == 0));
+        assert!(factorization.product().probably_eq_with_rng(&squared, 1e-9, &mut rng));
+
+        let poly: VecPoly<GF256> = random_monic(*degree, &mut rng);
+        let cubed = &(&poly * &poly) * &poly;
+        let factorization = cubed.factor_with_rng(&mut rng);
+        check_factorization(&cubed, &factorization);
+        assert!(factorization.product().probably_eq_with_rng(&cubed, 1e-9, &mut rng));
+    }
+}
+
+#[test]
+fn factorization_is_reproducible_and_splits_in_extensions() {
+    use num::Zero;
+    use crate::field::{Ext, ExtensionModulus, Fp};
+
+    /// x^2 + 1, which is irreducible over GF(3).
+    struct GF9Modulus;
+
+    impl ExtensionModulus<Fp<3>> for GF9Modulus {
+        fn modulus() -> VecPoly<Fp<3>> {
+            return VecPoly::new(vec![Fp::new(1), Fp::new(0), Fp::new(1)])
+        }
+    }
+    type GF9 = Ext<Fp<3>, GF9Modulus, 2>;
+
+    // x^2 + 1 is irreducible over GF(3), but splits as (x - i)(x + i) over GF(9)
+    let poly = VecPoly::new(vec![GF9::one(), GF9::zero(), GF9::one()]);
+    let factorization = poly.factor_with_rng(&mut StdRng::seed_from_u64(30));
+    assert_eq!(factorization.factors.len(), 2);
+    assert!(factorization.factors.iter().all(|(factor, m)| factor.degree() == Some(1) && *m == 1));
+    check_factorization(&poly, &factorization);
+
+    // equal-degree splitting draws from the rng, so a seed fixes the order of the factors
+    let product: VecPoly<Fp<65537>> = (0..6).fold(VecPoly::one(), |acc, c| &acc * &VecPoly::new(vec![Fp::new(1), Fp::new(c * 1000)]));
+    let split = |seed: u64| product.equal_degree_factorization_with_rng(1, &mut StdRng::seed_from_u64(seed));
+    assert_eq!(split(31), split(31));
+    assert_eq!(split(31).len(), 6);
+    assert_eq!(VecPoly::new(vec![Fp::<65537>::new(5)]).factor().factors, vec![]);
+}
@@ -0,0 +1,787 @@
+use rand::prelude::*;
+use rand::distributions::Standard;
+use rand::distributions::uniform::{SampleBorrow, SampleUniform, UniformInt, UniformSampler};
+use num::{Zero, One, Bounded};
+use std::any::{Any, TypeId};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt;
+use std::marker::PhantomData;
+use std::rc::Rc;
+use std::ops::{Add, Sub, Mul, Div, Neg};
+use alga::general::{AbstractMagma, Additive, Multiplicative, Identity, TwoSidedInverse};
+use crate::VecPoly;
+use crate::algebra::{Field, IntegralDomain};
+use crate::sample::{RandomFieldElement, WholeField};
+use super::{Cardinality, FieldInfo, Fp, ENUMERATION_LIMIT};
+
+/// BaseField is the arithmetic that a field has to support so that it can be extended.
+pub trait BaseField: Field + Neg<Output=Self> + Copy + PartialEq + fmt::Debug + 'static {}
+
+impl<T> BaseField for T where
+    T: Field + Neg<Output=T> + Copy + PartialEq + fmt::Debug + 'static {}
+
+/// ExtensionModulus provides the irreducible polynomial that defines an extension field.
+///
+/// Implement it on a marker type to configure an `Ext`:
+///
+/// ```
+/// use cs225_impl::VecPoly;
+/// use cs225_impl::field::{Ext, ExtensionModulus, Fp};
+///
+/// struct XSquaredPlusOne;
+///
+/// impl ExtensionModulus<Fp<3>> for XSquaredPlusOne {
+///     fn modulus() -> VecPoly<Fp<3>> {
+///         return VecPoly::new(vec![Fp::new(1), Fp::new(0), Fp::new(1)])
+///     }
+/// }
+///
+/// // GF(9) as GF(3)[x]/(x^2 + 1)
+/// type GF9 = Ext<Fp<3>, XSquaredPlusOne, 2>;
+/// ```
+pub trait ExtensionModulus<F>: 'static {
+    /// Returns the monic irreducible polynomial of the extension. Its degree has to match the
+    /// degree of the extension. See the `irreducible` module for a test and for known moduli.
+    /// Ext calls this once per thread and caches the result.
+    fn modulus() -> VecPoly<F>;
+}
+
+thread_local! {
+    /// The checked moduli of the extensions used on this thread, keyed by the type of the Ext.
+    static EXTENSION_MODULI: RefCell<HashMap<TypeId, Rc<dyn Any>>> = RefCell::new(HashMap::new());
+}
+
+/// Ext is an element of the extension field F[x]/(m(x)) of degree K, where m(x) is given by M.
+///
+/// Elements are stored as polynomials of degree less than K, lowest order term first. If m(x) is
+/// not irreducible the result is only a ring, and `inverse` returns None for zero divisors.
+pub struct Ext<F, M, const K: usize> {
+    coefficients: [F; K],
+    modulus: PhantomData<fn() -> M>,
+}
+
+impl<F: BaseField, M: ExtensionModulus<F>, const K: usize> Ext<F, M, K> {
+    /// Creates an element from its coefficients, lowest order term first.
+    pub fn new(coefficients: [F; K]) -> Self {
+        return Self { coefficients, modulus: PhantomData }
+    }
+
+    /// Returns the coefficients of the element, lowest order term first.
+    pub fn coefficients(&self) -> &[F; K] {
+        return &self.coefficients
+    }
+
+    /// Returns the generator x of the extension, the class of x in F[x]/(m(x)).
+    pub fn generator() -> Self {
+        return Self::reduce(&VecPoly::from_lowest_first(vec![F::zero(), F::one()]))
+    }
+
+    /// Returns the modulus, checking that it is monic of degree K the first time it is built on
+    /// this thread.
+    fn modulus() -> Rc<VecPoly<F>> {
+        let key = TypeId::of::<Self>();
+        if let Some(modulus) = EXTENSION_MODULI.with(|moduli| moduli.borrow().get(&key).cloned()) {
+            return modulus.downcast().expect("the moduli are keyed by the type of the extension")
+        }
+        let modulus = M::modulus();
+        assert_eq!(modulus.degree(), Some(K), "the extension modulus must have degree {}", K);
+        assert!(*modulus.leading_coefficient().unwrap() == F::one(), "the extension modulus must be monic");
+        let modulus = Rc::new(modulus);
+        EXTENSION_MODULI.with(|moduli| moduli.borrow_mut().insert(key, modulus.clone()));
+        return modulus
+    }
+
+    /// Returns the element as a polynomial of degree less than K.
+    fn to_poly(self) -> VecPoly<F> {
+        return VecPoly::from_lowest_first(self.coefficients.to_vec())
+    }
+
+    /// Reduces a polynomial modulo m(x).
+    fn reduce(poly: &VecPoly<F>) -> Self {
+        let remainder = poly % &*Self::modulus();
+        let mut coefficients = [F::zero(); K];
+        coefficients[..remainder.coefficients().len()].copy_from_slice(remainder.coefficients());
+        return Self::new(coefficients)
+    }
+
+    /// Raises the element to the power exp using repeated squaring.
+    pub fn pow(self, mut exp: u64) -> Self {
+        let mut result = Self::one();
+        let mut square = self;
+        while exp > 0 {
+            if exp & 1 == 1 {
+                result = result * square;
+            }
+            square = square * square;
+            exp >>= 1;
+        }
+        return result
+    }
+
+    /// Returns the multiplicative inverse of the element, or None if it is not invertible.
+    ///
+    /// This runs the extended Euclidean algorithm on the element and m(x): if
+    /// s(x)a(x) + t(x)m(x) = 1 then s(x) is the inverse of a(x).
+    pub fn inverse(self) -> Option<Self> {
+        let (gcd, s, _) = self.to_poly().extended_gcd(&Self::modulus());
+        // the gcd is monic, so it is one exactly when the element is invertible
+        if gcd.degree() != Some(0) {
+            return None
+        }
+        return Some(Self::reduce(&s))
+    }
+}
+
+impl<const P: u64, M: ExtensionModulus<Fp<P>>, const K: usize> Ext<Fp<P>, M, K> {
+    /// Applies the Frobenius automorphism a -> a^P, which fixes exactly the base field GF(P).
+    pub fn frobenius(self) -> Self {
+        return self.pow(P)
+    }
+
+    /// Returns the number of elements in the field, P^K, or None if it does not fit in a u64.
+    pub fn order() -> Option<u64> {
+        return P.checked_pow(K as u32)
+    }
+
+    /// Returns the index of the element when its coefficients are read as a base P number.
+    fn index(&self) -> u64 {
+        return self.coefficients.iter().rev().fold(0, |acc, c| acc * P + c.value())
+    }
+
+    /// Returns the element whose coefficients are the base P digits of index.
+    fn from_index(mut index: u64) -> Self {
+        let mut coefficients = [Fp::zero(); K];
+        for coef in coefficients.iter_mut() {
+            *coef = Fp::new(index % P);
+            index /= P;
+        }
+        return Self::new(coefficients)
+    }
+}
+
+impl<F: Clone, M, const K: usize> Clone for Ext<F, M, K> {
+    fn clone(&self) -> Self {
+        return Self { coefficients: self.coefficients.clone(), modulus: PhantomData }
+    }
+}
+
+impl<F: Copy, M, const K: usize> Copy for Ext<F, M, K> {}
+
+impl<F: PartialEq, M, const K: usize> PartialEq for Ext<F, M, K> {
+    fn eq(&self, other: &Self) -> bool {
+        return self.coefficients == other.coefficients
+    }
+}
+
+impl<F: Eq, M, const K: usize> Eq for Ext<F, M, K> {}
+
+impl<F: fmt::Debug, M, const K: usize> fmt::Debug for Ext<F, M, K> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return f.debug_tuple("Ext").field(&self.coefficients).finish()
+    }
+}
+
+/// Embeds an element of the base field as a constant of the extension.
+impl<F: BaseField, M: ExtensionModulus<F>, const K: usize> From<F> for Ext<F, M, K> {
+    fn from(value: F) -> Self {
+        let mut coefficients = [F::zero(); K];
+        coefficients[0] = value;
+        return Self::new(coefficients)
+    }
+}
+
+impl<F: BaseField, M: ExtensionModulus<F>, const K: usize> Add for Ext<F, M, K> {
+    type Output=Self;
+
+    fn add(mut self, other: Self) -> Self {
+        for (a, b) in self.coefficients.iter_mut().zip(other.coefficients.iter()) {
+            *a = *a + *b;
+        }
+        return self
+    }
+}
+
+impl<F: BaseField, M: ExtensionModulus<F>, const K: usize> Sub for Ext<F, M, K> {
+    type Output=Self;
+
+    fn sub(mut self, other: Self) -> Self {
+        for (a, b) in self.coefficients.iter_mut().zip(other.coefficients.iter()) {
+            *a = *a - *b;
+        }
+        return self
+    }
+}
+
+impl<F: BaseField, M: ExtensionModulus<F>, const K: usize> Mul for Ext<F, M, K> {
+    type Output=Self;
+
+    fn mul(self, other: Self) -> Self {
+        return Self::reduce(&self.to_poly().mul_fast(&other.to_poly()))
+    }
+}
+
+impl<F: BaseField, M: ExtensionModulus<F>, const K: usize> Div for Ext<F, M, K> {
+    type Output=Self;
+
+    /// Divides two field elements. Panics if other is not invertible.
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn div(self, other: Self) -> Self {
+        let inverse = other.inverse().expect("division by a non-invertible element");
+        return self * inverse
+    }
+}
+
+impl<F: BaseField, M: ExtensionModulus<F>, const K: usize> Neg for Ext<F, M, K> {
+    type Output=Self;
+
+    fn neg(mut self) -> Self {
+        for a in self.coefficients.iter_mut() {
+            *a = -*a;
+        }
+        return self
+    }
+}
+
+impl<F: BaseField, M: ExtensionModulus<F>, const K: usize> Zero for Ext<F, M, K> {
+    fn zero() -> Self {
+        return Self::new([F::zero(); K])
+    }
+
+    fn is_zero(&self) -> bool {
+        return self.coefficients.iter().all(|c| c.is_zero())
+    }
+}
+
+impl<F: BaseField, M: ExtensionModulus<F>, const K: usize> One for Ext<F, M, K> {
+    fn one() -> Self {
+        return Self::from(F::one())
+    }
+}
+
+/// The bounds are the elements with every coefficient at the bound of the base field. For
+/// GF(p^k) these are the elements with index 0 and p^k - 1.
+impl<F: BaseField + Bounded, M: ExtensionModulus<F>, const K: usize> Bounded for Ext<F, M, K> {
+    fn min_value() -> Self {
+        return Self::new([F::min_value(); K])
+    }
+
+    fn max_value() -> Self {
+        return Self::new([F::max_value(); K])
+    }
+}
+
+impl<F: BaseField, M: ExtensionModulus<F>, const K: usize> AbstractMagma<Additive> for Ext<F, M, K> {
+    fn operate(&self, right: &Self) -> Self {
+        return *self + *right
+    }
+}
+
+impl<F: BaseField, M: ExtensionModulus<F>, const K: usize> AbstractMagma<Multiplicative> for Ext<F, M, K> {
+    fn operate(&self, right: &Self) -> Self {
+        return *self * *right
+    }
+}
+
+impl<F: BaseField, M: ExtensionModulus<F>, const K: usize> Identity<Additive> for Ext<F, M, K> {
+    fn identity() -> Self {
+        return Self::zero()
+    }
+}
+
+impl<F: BaseField, M: ExtensionModulus<F>, const K: usize> Identity<Multiplicative> for Ext<F, M, K> {
+    fn identity() -> Self {
+        return Self::one()
+    }
+}
+
+impl<F: BaseField, M: ExtensionModulus<F>, const K: usize> TwoSidedInverse<Additive> for Ext<F, M, K> {
+    fn two_sided_inverse(&self) -> Self {
+        return -*self
+    }
+}
+
+impl<F: BaseField, M: ExtensionModulus<F>, const K: usize> TwoSidedInverse<Multiplicative> for Ext<F, M, K> {
+    /// Returns the multiplicative inverse. Panics if the element is not invertible.
+    fn two_sided_inverse(&self) -> Self {
+        return self.inverse().expect("element has no multiplicative inverse")
+    }
+}
+
+impl_abstract_field!([F: BaseField, M: ExtensionModulus<F>, const K: usize] Ext<F, M, K>);
+
+/// This holds as long as the modulus is irreducible, which Ext assumes but does not check.
+impl<F: BaseField, M: ExtensionModulus<F>, const K: usize> IntegralDomain for Ext<F, M, K> {}
+impl<F: BaseField, M: ExtensionModulus<F>, const K: usize> Field for Ext<F, M, K> {}
+
+/// Samples an element uniformly from the whole extension.
+impl<const P: u64, M: ExtensionModulus<Fp<P>>, const K: usize> Distribution<Ext<Fp<P>, M, K>> for Standard {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Ext<Fp<P>, M, K> {
+        let mut coefficients = [Fp::zero(); K];
+        for coef in coefficients.iter_mut() {
+            *coef = rng.gen();
+        }
+        return Ext::new(coefficients)
+    }
+}
+
+/// Identity tests draw from the whole field of P^K elements. Unlike `UniformExt`, this works
+/// even when P^K does not fit in a u64, since every coefficient is drawn independently.
+impl<const P: u64, M: ExtensionModulus<Fp<P>>, const K: usize> RandomFieldElement for Ext<Fp<P>, M, K> {
+    type SampleSet = WholeField<Self>;
+
+    fn sample_set() -> WholeField<Self> {
+        return WholeField::new((P as f64).powi(K as i32))
+    }
+}
+
+impl<const P: u64, M: ExtensionModulus<Fp<P>>, const K: usize> FieldInfo for Ext<Fp<P>, M, K> {
+    fn characteristic() -> u64 {
+        return P
+    }
+
+    fn cardinality() -> Cardinality {
+        return Cardinality::Finite { characteristic: P, degree: K as u32 }
+    }
+
+    fn elements() -> Option<Vec<Self>> {
+        let order = Self::order().filter(|order| *order <= ENUMERATION_LIMIT)?;
+        return Some((0..order).map(Self::from_index).collect())
+    }
+}
+
+/// UniformExt samples elements of GF(P^K) whose index, the coefficients read as a base P
+/// number, lies in a range. This requires P^K to fit in a u64.
+pub struct UniformExt<const P: u64, M, const K: usize> {
+    inner: UniformInt<u64>,
+    modulus: PhantomData<fn() -> M>,
+}
+
+impl<const P: u64, M: ExtensionModulus<Fp<P>>, const K: usize> UniformSampler for UniformExt<P, M, K> {
+    type X = Ext<Fp<P>, M, K>;
+
+    fn new<B1, B2>(low: B1, high: B2) -> Self where
+        B1: SampleBorrow<Self::X> + Sized,
+        B2: SampleBorrow<Self::X> + Sized {
+        assert!(Self::X::order().is_some(), "GF({}^{}) is too large to sample by index", P, K);
+        return UniformExt { inner: UniformInt::<u64>::new(low.borrow().index(), high.borrow().index()), modulus: PhantomData }
+    }
+
+    fn new_inclusive<B1, B2>(low: B1, high: B2) -> Self where
+        B1: SampleBorrow<Self::X> + Sized,
+        B2: SampleBorrow<Self::X> + Sized {
+        assert!(Self::X::order().is_some(), "GF({}^{}) is too large to sample by index", P, K);
+        return UniformExt { inner: UniformInt::<u64>::new_inclusive(low.borrow().index(), high.borrow().index()), modulus: PhantomData }
+    }
+
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Self::X {
+        return Ext::from_index(self.inner.sample(rng))
+    }
+}
+
+impl<const P: u64, M: ExtensionModulus<Fp<P>>, const K: usize> SampleUniform for Ext<Fp<P>, M, K> {
+    type Sampler = UniformExt<P, M, K>;
+}
+
+/// GF2k is an element of the binary field GF(2^K), stored as a bit vector.
+///
+/// Bit i holds the coefficient of x^i. MODULUS is the irreducible polynomial in the same
+/// encoding, including the x^K bit, so K can be at most 63. For example the field used by AES is
+/// `GF2k<8, 0x11B>`, which is x^8 + x^4 + x^3 + x + 1. A MODULUS whose degree is not K fails to
+/// compile:
+///
+/// ```compile_fail
+/// use cs225_impl::field::GF2k;
+///
+/// let x = GF2k::<8, 0x1B>::new(1);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct GF2k<const K: u32, const MODULUS: u64> {
+    bits: u64,
+}
+
+/// The field with 256 elements used by AES.
+pub type GF256 = GF2k<8, 0x11B>;
+
+impl<const K: u32, const MODULUS: u64> GF2k<K, MODULUS> {
+    /// Evaluating this fails to compile unless MODULUS has degree K and K is in [1, 63].
+    const VALID_MODULUS: () = assert!(K >= 1 && K <= 63 && MODULUS >> K == 1, "the modulus must have degree K <= 63");
+
+    /// Creates an element from its bit representation, reduced modulo the field polynomial.
+    pub fn new(bits: u64) -> Self {
+        return Self::reduce(bits as u128)
+    }
+
+    /// Returns the bit representation of the element.
+    pub fn bits(&self) -> u64 {
+        return self.bits
+    }
+
+    fn reduce(mut bits: u128) -> Self {
+        let _: () = Self::VALID_MODULUS;
+        for shift in (0..(128 - K)).rev() {
+            if bits >> (shift + K) & 1 == 1 {
+                bits ^= (MODULUS as u128) << shift;
+            }
+        }
+        return Self { bits: bits as u64 }
+    }
+
+    /// Raises the element to the power exp using repeated squaring.
+    pub fn pow(self, mut exp: u64) -> Self {
+        let mut result = Self::one();
+        let mut square = self;
+        while exp > 0 {
+            if exp & 1 == 1 {
+                result = result * square;
+            }
+            square = square * square;
+            exp >>= 1;
+        }
+        return result
+    }
+
+    /// Returns the multiplicative inverse of the element, or None if the element is zero.
+    ///
+    /// The multiplicative group has order 2^K - 1, so the inverse is a^(2^K - 2).
+    pub fn inverse(self) -> Option<Self> {
+        if self.bits == 0 {
+            return None
+        }
+        return Some(self.pow((1 << K) - 2))
+    }
+
+    /// Applies the Frobenius automorphism a -> a^2, which fixes exactly GF(2).
+    pub fn frobenius(self) -> Self {
+        return self * self
+    }
+}
+
+impl<const K: u32, const MODULUS: u64> From<Fp<2>> for GF2k<K, MODULUS> {
+    fn from(value: Fp<2>) -> Self {
+        return Self { bits: value.value() }
+    }
+}
+
+impl<const K: u32, const MODULUS: u64> Add for GF2k<K, MODULUS> {
+    type Output=Self;
+
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn add(self, other: Self) -> Self {
+        return Self { bits: self.bits ^ other.bits }
+    }
+}
+
+impl<const K: u32, const MODULUS: u64> Sub for GF2k<K, MODULUS> {
+    type Output=Self;
+
+    // in characteristic two subtraction is the same as addition
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn sub(self, other: Self) -> Self {
+        return Self { bits: self.bits ^ other.bits }
+    }
+}
+
+impl<const K: u32, const MODULUS: u64> Mul for GF2k<K, MODULUS> {
+    type Output=Self;
+
+    /// Multiplies without carries and reduces the product modulo the field polynomial.
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn mul(self, other: Self) -> Self {
+        let mut product: u128 = 0;
+        for i in 0..K {
+            if other.bits >> i & 1 == 1 {
+                product ^= (self.bits as u128) << i;
+            }
+        }
+        return Self::reduce(product)
+    }
+}
+
+impl<const K: u32, const MODULUS: u64> Div for GF2k<K, MODULUS> {
+    type Output=Self;
+
+    /// Divides two field elements. Panics if other is zero.
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn div(self, other: Self) -> Self {
+        let inverse = other.inverse().expect("division by zero in GF(2^k)");
+        return self * inverse
+    }
+}
+
+impl<const K: u32, const MODULUS: u64> Neg for GF2k<K, MODULUS> {
+    type Output=Self;
+
+    fn neg(self) -> Self {
+        return self
+    }
+}
+
+impl<const K: u32, const MODULUS: u64> Zero for GF2k<K, MODULUS> {
+    fn zero() -> Self {
+        return Self { bits: 0 }
+    }
+
+    fn is_zero(&self) -> bool {
+        return self.bits == 0
+    }
+}
+
+impl<const K: u32, const MODULUS: u64> One for GF2k<K, MODULUS> {
+    fn one() -> Self {
+        return Self { bits: 1 }
+    }
+}
+
+/// The bounds are the all zero and all one bit vectors.
+impl<const K: u32, const MODULUS: u64> Bounded for GF2k<K, MODULUS> {
+    fn min_value() -> Self {
+        return Self { bits: 0 }
+    }
+
+    fn max_value() -> Self {
+        return Self { bits: (1 << K) - 1 }
+    }
+}
+
+impl<const K: u32, const MODULUS: u64> AbstractMagma<Additive> for GF2k<K, MODULUS> {
+    fn operate(&self, right: &Self) -> Self {
+        return *self + *right
+    }
+}
+
+impl<const K: u32, const MODULUS: u64> AbstractMagma<Multiplicative> for GF2k<K, MODULUS> {
+    fn operate(&self, right: &Self) -> Self {
+        return *self * *right
+    }
+}
+
+impl<const K: u32, const MODULUS: u64> Identity<Additive> for GF2k<K, MODULUS> {
+    fn identity() -> Self {
+        return Self::zero()
+    }
+}
+
+impl<const K: u32, const MODULUS: u64> Identity<Multiplicative> for GF2k<K, MODULUS> {
+    fn identity() -> Self {
+        return Self::one()
+    }
+}
+
+impl<const K: u32, const MODULUS: u64> TwoSidedInverse<Additive> for GF2k<K, MODULUS> {
+    fn two_sided_inverse(&self) -> Self {
+        return *self
+    }
+}
+
+impl<const K: u32, const MODULUS: u64> TwoSidedInverse<Multiplicative> for GF2k<K, MODULUS> {
+    /// Returns the multiplicative inverse. Panics if the element is zero.
+    fn two_sided_inverse(&self) -> Self {
+        return self.inverse().expect("zero has no multiplicative inverse")
+    }
+}
+
+impl_abstract_field!([const K: u32, const MODULUS: u64] GF2k<K, MODULUS>);
+
+impl<const K: u32, const MODULUS: u64> IntegralDomain for GF2k<K, MODULUS> {}
+impl<const K: u32, const MODULUS: u64> Field for GF2k<K, MODULUS> {}
+
+/// Samples an element uniformly from the whole field.
+impl<const K: u32, const MODULUS: u64> Distribution<GF2k<K, MODULUS>> for Standard {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> GF2k<K, MODULUS> {
+        return GF2k { bits: rng.gen_range(0, 1 << K) }
+    }
+}
+
+/// Identity tests draw from the whole field of 2^K elements.
+impl<const K: u32, const MODULUS: u64> RandomFieldElement for GF2k<K, MODULUS> {
+    type SampleSet = WholeField<Self>;
+
+    fn sample_set() -> WholeField<Self> {
+        return WholeField::new(2f64.powi(K as i32))
+    }
+}
+
+impl<const K: u32, const MODULUS: u64> FieldInfo for GF2k<K, MODULUS> {
+    fn characteristic() -> u64 {
+        return 2
+    }
+
+    fn cardinality() -> Cardinality {
+        return Cardinality::Finite { characteristic: 2, degree: K }
+    }
+
+    fn elements() -> Option<Vec<Self>> {
+        if 1 << K > ENUMERATION_LIMIT {
+            return None
+        }
+        return Some((0..1 << K).map(|bits| Self { bits }).collect())
+    }
+}
+
+/// UniformGF2k samples elements of GF(2^K) whose bit representation lies in a range.
+#[derive(Clone, Copy, Debug)]
+pub struct UniformGF2k<const K: u32, const MODULUS: u64>(UniformInt<u64>);
+
+impl<const K: u32, const MODULUS: u64> UniformSampler for UniformGF2k<K, MODULUS> {
+    type X = GF2k<K, MODULUS>;
+
+    fn new<B1, B2>(low: B1, high: B2) -> Self where
+        B1: SampleBorrow<Self::X> + Sized,
+        B2: SampleBorrow<Self::X> + Sized {
+        return UniformGF2k(UniformInt::<u64>::new(low.borrow().bits, high.borrow().bits))
+    }
+
+    fn new_inclusive<B1, B2>(low: B1, high: B2) -> Self where
+        B1: SampleBorrow<Self::X> + Sized,
+        B2: SampleBorrow<Self::X> + Sized {
+        return UniformGF2k(UniformInt::<u64>::new_inclusive(low.borrow().bits, high.borrow().bits))
+    }
+
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Self::X {
+        return GF2k { bits: self.0.sample(rng) }
+    }
+}
+
+impl<const K: u32, const MODULUS: u64> SampleUniform for GF2k<K, MODULUS> {
+    type Sampler = UniformGF2k<K, MODULUS>;
+}
+
+/// x^2 + 1, which is irreducible over GF(3) since -1 is not a square mod 3.
+#[cfg(test)]
+struct GF9Modulus;
+
+#[cfg(test)]
+impl ExtensionModulus<Fp<3>> for GF9Modulus {
+    fn modulus() -> VecPoly<Fp<3>> {
+        return VecPoly::new(vec![Fp::new(1), Fp::new(0), Fp::new(1)])
+    }
+}
+
+#[cfg(test)]
+type GF9 = Ext<Fp<3>, GF9Modulus, 2>;
+
+/// x^20 + x^5 + 2, which is irreducible over GF(3) as the lifting test checks.
+#[cfg(test)]
+struct GF3To20Modulus;
+
+#[cfg(test)]
+impl ExtensionModulus<Fp<3>> for GF3To20Modulus {
+    fn modulus() -> VecPoly<Fp<3>> {
+        let mut coefficients = vec![Fp::zero(); 21];
+        coefficients[0] = Fp::new(1);
+        coefficients[15] = Fp::new(1);
+        coefficients[20] = Fp::new(2);
+        return VecPoly::new(coefficients)
+    }
+}
+
+#[test]
+fn gf9_field_axioms() {
+    use super::check_field_axioms;
+
+    let elements: Vec<GF9> = (0..9).map(GF9::from_index).collect();
+    check_field_axioms(&elements);
+    for a in elements.iter().filter(|a| !a.is_zero()) {
+        assert_eq!(a.pow(8), GF9::one());
+    }
+}
+
+#[test]
+fn gf9_frobenius() {
+    for index in 0..9 {
+        let a = GF9::from_index(index);
+        assert_eq!(a.frobenius(), a * a * a);
+        assert_eq!(a.frobenius().frobenius(), a);
+        let b = GF9::from_index((index * 5 + 1) % 9);
+        assert_eq!((a + b).frobenius(), a.frobenius() + b.frobenius());
+    }
+    // the Frobenius fixes exactly the base field
+    let fixed = (0..9).map(GF9::from_index).filter(|a| a.frobenius() == *a).count();
+    assert_eq!(fixed, 3);
+}
+
+#[test]
+fn extension_moduli_are_built_once_per_thread() {
+    use std::cell::Cell;
+
+    thread_local! {
+        static BUILT: Cell<usize> = const { Cell::new(0) };
+    }
+
+    struct Counted;
+
+    impl ExtensionModulus<Fp<3>> for Counted {
+        fn modulus() -> VecPoly<Fp<3>> {
+            BUILT.with(|built| built.set(built.get() + 1));
+            return VecPoly::new(vec![Fp::new(1), Fp::new(0), Fp::new(1)])
+        }
+    }
+
+    let x = Ext::<Fp<3>, Counted, 2>::generator();
+    assert_eq!(x.pow(4), Ext::one());
+    assert_eq!(x.inverse(), Some(-x));
+    assert_eq!(BUILT.with(Cell::get), 1);
+}
+
+#[test]
+fn reducible_modulus_has_zero_divisors() {
+    // x^2 - 1 = (x - 1)(x + 1) over GF(3)
+    struct Reducible;
+
+    impl ExtensionModulus<Fp<3>> for Reducible {
+        fn modulus() -> VecPoly<Fp<3>> {
+            return VecPoly::new(vec![Fp::new(1), Fp::new(0), Fp::from_i64(-1)])
+        }
+    }
+
+    let x_plus_one = Ext::<Fp<3>, Reducible, 2>::new([Fp::new(1), Fp::new(1)]);
+    assert_eq!(x_plus_one.inverse(), None);
+    let x = Ext::<Fp<3>, Reducible, 2>::generator();
+    assert_eq!(x.inverse(), Some(x));
+}
+
+#[test]
+fn gf256_known_values() {
+    use super::check_field_axioms;
+
+    // from FIPS-197
+    assert_eq!(GF256::new(0x57) * GF256::new(0x83), GF256::new(0xC1));
+    assert_eq!(GF256::new(0x53).inverse(), Some(GF256::new(0xCA)));
+    let mut rng = StdRng::seed_from_u64(256);
+    let elements: Vec<GF256> = (0..12).map(|_| rng.gen()).collect();
+    check_field_axioms(&elements);
+    for bits in 1..256 {
+        let a = GF256::new(bits);
+        assert_eq!(a * a.inverse().unwrap(), GF256::one());
+        assert_eq!(a.frobenius().pow(128), a);
+    }
+}
+
+#[test]
+fn lifted_pit_detects_nonzero_function_zero_polynomial() {
+    assert!(GF3To20Modulus::modulus().is_irreducible());
+
+    // x^3 - x vanishes on all of GF(3), but it is not the zero polynomial. Over GF(3^20) it
+    // only has three roots.
+    let poly = VecPoly::new(vec![Fp::<3>::one(), Fp::zero(), -Fp::one(), Fp::zero()]);
+    let lifted: VecPoly<Ext<Fp<3>, GF3To20Modulus, 20>> = poly.lift();
+    assert_eq!(lifted.probably_zero(), false);
+
+    // likewise x^2 + x vanishes on GF(2), but not on GF(2^63).
+    let poly = VecPoly::new(vec![Fp::<2>::one(), Fp::one(), Fp::zero()]);
+    let lifted: VecPoly<GF2k<63, { (1 << 63) | 0b11 }>> = poly.lift();
+    assert_eq!(lifted.probably_zero(), false);
+
+    let zero = VecPoly::new(vec![Fp::<3>::zero(); 4]);
+    let lifted: VecPoly<Ext<Fp<3>, GF3To20Modulus, 20>> = zero.lift();
+    assert_eq!(lifted.probably_zero(), true);
+
+    // over GF(3) itself the test refuses to run, and lifting is the way out
+    use crate::IdentityTest;
+    let mut rng = StdRng::seed_from_u64(4);
+    let cubic = VecPoly::new(vec![Fp::<3>::one(), Fp::zero(), -Fp::one(), Fp::zero()]);
+    assert_eq!(cubic.try_test_zero_with_rng(1e-9, &mut rng), None);
+    let verdict = cubic.test_zero_lifted_with_rng::<Ext<Fp<3>, GF3To20Modulus, 20>, _>(1e-9, &mut rng);
+    assert!(verdict.is_some_and(|v| !v.is_probably_zero()));
+}
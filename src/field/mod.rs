@@ -0,0 +1,175 @@
+//! Finite fields that can be used as coefficients for the polynomials in this crate.
+//!
+//! Unlike floats, arithmetic in these fields is exact, so the polynomial identity test never
+//! reports a false "nonzero" because of rounding.
+
+/// Implements the alga marker traits that make up `AbstractField` for a type that already
+/// implements `AbstractMagma`, `TwoSidedInverse` and `Identity` for both operators.
+///
+/// The generics of the type are passed in brackets, for example
+/// `impl_abstract_field!([const P: u64] Fp<P>);`.
+macro_rules! impl_abstract_field {
+    ([$($generics:tt)*] $t:ty) => {
+        impl<$($generics)*> alga::general::AbstractSemigroup<alga::general::Additive> for $t {}
+        impl<$($generics)*> alga::general::AbstractMonoid<alga::general::Additive> for $t {}
+        impl<$($generics)*> alga::general::AbstractQuasigroup<alga::general::Additive> for $t {}
+        impl<$($generics)*> alga::general::AbstractLoop<alga::general::Additive> for $t {}
+        impl<$($generics)*> alga::general::AbstractGroup<alga::general::Additive> for $t {}
+        impl<$($generics)*> alga::general::AbstractGroupAbelian<alga::general::Additive> for $t {}
+        impl<$($generics)*> alga::general::AbstractSemigroup<alga::general::Multiplicative> for $t {}
+        impl<$($generics)*> alga::general::AbstractMonoid<alga::general::Multiplicative> for $t {}
+        impl<$($generics)*> alga::general::AbstractQuasigroup<alga::general::Multiplicative> for $t {}
+        impl<$($generics)*> alga::general::AbstractLoop<alga::general::Multiplicative> for $t {}
+        impl<$($generics)*> alga::general::AbstractGroup<alga::general::Multiplicative> for $t {}
+        impl<$($generics)*> alga::general::AbstractGroupAbelian<alga::general::Multiplicative> for $t {}
+        impl<$($generics)*> alga::general::AbstractRing for $t {}
+        impl<$($generics)*> alga::general::AbstractRingCommutative for $t {}
+        impl<$($generics)*> alga::general::AbstractField for $t {}
+    };
+}
+
+mod prime;
+mod extension;
+
+pub use prime::{DynFp, Fp, UniformDynFp, UniformFp};
+pub(crate) use prime::{add_mod, mul_mod, pow_mod, sub_mod};
+pub use extension::{BaseField, ExtensionModulus, Ext, UniformExt, GF2k, UniformGF2k, GF256};
+
+/// The largest field that `FieldInfo::elements` lists.
+pub const ENUMERATION_LIMIT: u64 = 1 << 16;
+
+/// Cardinality is the number of elements of a field. Every finite field has p^k elements, where
+/// the prime p is its characteristic, so that is how finite sizes are stored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Cardinality {
+    /// A field with characteristic^degree elements.
+    Finite { characteristic: u64, degree: u32 },
+    /// A field with infinitely many elements.
+    Infinite,
+}
+
+impl Cardinality {
+    /// Returns the number of elements, or None if it is infinite or does not fit in a u128.
+    pub fn to_u128(&self) -> Option<u128> {
+        match self {
+            Cardinality::Finite { characteristic, degree } => return (*characteristic as u128).checked_pow(*degree),
+            Cardinality::Infinite => return None,
+        }
+    }
+
+    /// Returns the number of elements as a float, which is infinite for infinite fields.
+    pub fn to_f64(&self) -> f64 {
+        match self {
+            Cardinality::Finite { characteristic, degree } => return (*characteristic as f64).powi(*degree as i32),
+            Cardinality::Infinite => return f64::INFINITY,
+        }
+    }
+}
+
+/// FieldInfo describes the size of a field, which is what the Schwartz-Zippel error d/|F|
+/// depends on. It is also implemented by infinite integral domains like the integers, where the
+/// error depends on the sample set instead.
+pub trait FieldInfo: Sized {
+    /// Returns the characteristic, the smallest n such that 1 + ... + 1 (n times) is zero, or 0
+    /// if there is no such n.
+    fn characteristic() -> u64;
+
+    /// Returns the number of elements of the field.
+    fn cardinality() -> Cardinality;
+
+    /// Returns every element of the field, or None if the field is infinite or has more than
+    /// ENUMERATION_LIMIT elements.
+    fn elements() -> Option<Vec<Self>> {
+        return None
+    }
+
+    /// Returns whether element belongs to the field described by this trait. Types that carry
+    /// their field in the value, like DynFp, override this to compare the two.
+    fn contains(_element: &Self) -> bool {
+        return true
+    }
+}
+
+/// Floats approximate the reals, so they are treated as an infinite field of characteristic 0.
+impl FieldInfo for f64 {
+    fn characteristic() -> u64 {
+        return 0
+    }
+
+    fn cardinality() -> Cardinality {
+        return Cardinality::Infinite
+    }
+}
+
+/// The integers are an infinite integral domain of characteristic 0.
+impl FieldInfo for num::BigInt {
+    fn characteristic() -> u64 {
+        return 0
+    }
+
+    fn cardinality() -> Cardinality {
+        return Cardinality::Infinite
+    }
+}
+
+/// The rationals are an infinite field of characteristic 0.
+impl FieldInfo for num::BigRational {
+    fn characteristic() -> u64 {
+        return 0
+    }
+
+    fn cardinality() -> Cardinality {
+        return Cardinality::Infinite
+    }
+}
+
+impl FieldInfo for f32 {
+    fn characteristic() -> u64 {
+        return 0
+    }
+
+    fn cardinality() -> Cardinality {
+        return Cardinality::Infinite
+    }
+}
+
+/// Checks the field axioms on every triple of the given elements.
+#[cfg(test)]
+pub(crate) fn check_field_axioms<F: BaseField>(elements: &[F]) {
+    for &a in elements {
+        assert_eq!(a + F::zero(), a);
+        assert_eq!(a * F::one(), a);
+        assert_eq!(a + (-a), F::zero());
+        if !a.is_zero() {
+            assert_eq!(a * (F::one() / a), F::one());
+        }
+        for &b in elements {
+            assert_eq!(a + b, b + a);
+            assert_eq!(a * b, b * a);
+            assert_eq!((a - b) + b, a);
+            for &c in elements {
+                assert_eq!((a + b) + c, a + (b + c));
+                assert_eq!((a * b) * c, a * (b * c));
+                assert_eq!(a * (b + c), a * b + a * c);
+            }
+        }
+    }
+}
+
+
+#[test]
+fn cardinalities_of_the_fields() {
+    assert_eq!(Fp::<7>::cardinality(), Cardinality::Finite { characteristic: 7, degree: 1 });
+    assert_eq!(GF256::cardinality().to_u128(), Some(256));
+    assert_eq!(GF256::characteristic(), 2);
+    assert_eq!(f64::cardinality().to_f64(), f64::INFINITY);
+    assert_eq!(Cardinality::Finite { characteristic: 1 << 62, degree: 3 }.to_u128(), None);
+
+    let elements = Fp::<7>::elements().unwrap();
+    assert_eq!(elements.len(), 7);
+    assert!((0..7).all(|v| elements.contains(&Fp::new(v))));
+    assert_eq!(GF256::elements().unwrap().len(), 256);
+    // one more than the enumeration limit
+    assert!(Fp::<65537>::elements().is_none());
+    assert_eq!(f64::elements(), None);
+}
@@ -0,0 +1,786 @@
+use rand::prelude::*;
+use rand::distributions::Standard;
+use rand::distributions::uniform::{SampleBorrow, SampleUniform, UniformInt, UniformSampler};
+use num::{Zero, One, Bounded};
+use std::cell::Cell;
+use std::fmt;
+use std::ops::{Add, Sub, Mul, Div, Neg};
+use alga::general::{AbstractMagma, Additive, Multiplicative, Identity, TwoSidedInverse};
+use crate::algebra::{Field, IntegralDomain};
+use crate::sample::{RandomFieldElement, WholeField};
+use super::{Cardinality, FieldInfo, ENUMERATION_LIMIT};
+
+/// Returns a + b mod m, assuming both a and b are already reduced.
+pub(crate) fn add_mod(a: u64, b: u64, m: u64) -> u64 {
+    return ((a as u128 + b as u128) % m as u128) as u64
+}
+
+/// Returns a - b mod m, assuming both a and b are already reduced.
+pub(crate) fn sub_mod(a: u64, b: u64, m: u64) -> u64 {
+    if a >= b {
+        return a - b
+    }
+    return m - (b - a)
+}
+
+/// Returns a * b mod m, using a 128 bit intermediate so that no overflow occurs.
+pub(crate) fn mul_mod(a: u64, b: u64, m: u64) -> u64 {
+    return ((a as u128 * b as u128) % m as u128) as u64
+}
+
+/// Returns base^exp mod m using repeated squaring.
+pub(crate) fn pow_mod(base: u64, mut exp: u64, m: u64) -> u64 {
+    let mut result = 1 % m;
+    let mut square = base % m;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = mul_mod(result, square, m);
+        }
+        square = mul_mod(square, square, m);
+        exp >>= 1;
+    }
+    return result
+}
+
+/// Fp is an element of the prime field GF(P), where the modulus is fixed at compile time.
+///
+/// P must be a prime smaller than 2^63. Elements are always stored in their canonical form, a
+/// value in [0, P). A P outside [2, 2^63) fails to compile:
+///
+/// ```compile_fail
+/// use cs225_impl::field::Fp;
+///
+/// let x = Fp::<0>::new(1);
+/// ```
+///
+/// Primality is too expensive to check at compile time, so debug builds check it whenever an
+/// inverse is computed instead.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Fp<const P: u64> {
+    value: u64,
+}
+
+impl<const P: u64> Fp<P> {
+    /// Evaluating this fails to compile unless P is in [2, 2^63).
+    const VALID_MODULUS: () = assert!(P >= 2 && P < 1 << 63, "the modulus of Fp must be in [2, 2^63)");
+
+    /// Creates the element of GF(P) that is congruent to value.
+    pub fn new(value: u64) -> Self {
+        let _: () = Self::VALID_MODULUS;
+        return Self { value: value % P }
+    }
+
+    /// Creates the element of GF(P) that is congruent to a signed value.
+    pub fn from_i64(value: i64) -> Self {
+        let _: () = Self::VALID_MODULUS;
+        return Self { value: (value as i128).rem_euclid(P as i128) as u64 }
+    }
+
+    /// Returns the canonical representative of the element, in [0, P).
+    pub fn value(&self) -> u64 {
+        return self.value
+    }
+
+    /// Returns the modulus of the field.
+    pub fn modulus() -> u64 {
+        return P
+    }
+
+    /// Raises the element to the power exp using repeated squaring.
+    pub fn pow(self, exp: u64) -> Self {
+        return Self { value: pow_mod(self.value, exp, P) }
+    }
+
+    /// Returns the multiplicative inverse of the element, or None if the element is zero.
+    ///
+    /// By Fermat's little theorem a^(P-1) = 1, so the inverse is a^(P-2).
+    pub fn inverse(self) -> Option<Self> {
+        if self.value == 0 {
+            return None
+        }
+        let inverse = self.pow(P - 2);
+        debug_assert_eq!(mul_mod(inverse.value, self.value, P), 1, "{} is not a prime modulus", P);
+        return Some(inverse)
+    }
+}
+
+impl<const P: u64> fmt::Display for Fp<P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return write!(f, "{}", self.value)
+    }
+}
+
+impl<const P: u64> From<u64> for Fp<P> {
+    fn from(value: u64) -> Self {
+        return Self::new(value)
+    }
+}
+
+impl<const P: u64> Add for Fp<P> {
+    type Output=Self;
+
+    fn add(self, other: Self) -> Self {
+        return Self { value: add_mod(self.value, other.value, P) }
+    }
+}
+
+impl<const P: u64> Sub for Fp<P> {
+    type Output=Self;
+
+    fn sub(self, other: Self) -> Self {
+        return Self { value: sub_mod(self.value, other.value, P) }
+    }
+}
+
+impl<const P: u64> Mul for Fp<P> {
+    type Output=Self;
+
+    fn mul(self, other: Self) -> Self {
+        return Self { value: mul_mod(self.value, other.value, P) }
+    }
+}
+
+impl<const P: u64> Div for Fp<P> {
+    type Output=Self;
+
+    /// Divides two field elements. Panics if other is zero.
+    fn div(self, other: Self) -> Self {
+        let inverse = other.inverse().expect("division by zero in GF(p)");
+        return Self { value: mul_mod(self.value, inverse.value, P) }
+    }
+}
+
+impl<const P: u64> Neg for Fp<P> {
+    type Output=Self;
+
+    fn neg(self) -> Self {
+        return Self { value: sub_mod(0, self.value, P) }
+    }
+}
+
+impl<const P: u64> Zero for Fp<P> {
+    fn zero() -> Self {
+        return Self { value: 0 }
+    }
+
+    fn is_zero(&self) -> bool {
+        return self.value == 0
+    }
+}
+
+impl<const P: u64> One for Fp<P> {
+    fn one() -> Self {
+        let _: () = Self::VALID_MODULUS;
+        return Self { value: 1 % P }
+    }
+}
+
+/// The canonical representatives are bounded by 0 and P-1.
+impl<const P: u64> Bounded for Fp<P> {
+    fn min_value() -> Self {
+        return Self { value: 0 }
+    }
+
+    fn max_value() -> Self {
+        return Self { value: P - 1 }
+    }
+}
+
+impl<const P: u64> AbstractMagma<Additive> for Fp<P> {
+    fn operate(&self, right: &Self) -> Self {
+        return *self + *right
+    }
+}
+
+impl<const P: u64> AbstractMagma<Multiplicative> for Fp<P> {
+    fn operate(&self, right: &Self) -> Self {
+        return *self * *right
+    }
+}
+
+impl<const P: u64> Identity<Additive> for Fp<P> {
+    fn identity() -> Self {
+        return Self::zero()
+    }
+}
+
+impl<const P: u64> Identity<Multiplicative> for Fp<P> {
+    fn identity() -> Self {
+        return Self::one()
+    }
+}
+
+impl<const P: u64> TwoSidedInverse<Additive> for Fp<P> {
+    fn two_sided_inverse(&self) -> Self {
+        return -*self
+    }
+}
+
+impl<const P: u64> TwoSidedInverse<Multiplicative> for Fp<P> {
+    /// Returns the multiplicative inverse. Panics if the element is zero.
+    fn two_sided_inverse(&self) -> Self {
+        return self.inverse().expect("zero has no multiplicative inverse")
+    }
+}
+
+impl_abstract_field!([const P: u64] Fp<P>);
+
+impl<const P: u64> IntegralDomain for Fp<P> {}
+/// Long products use the number-theoretic transform when P supports it.
+impl<const P: u64> Field for Fp<P> {
+    fn multiply_polynomials(a: &[Self], b: &[Self]) -> Vec<Self> {
+        return crate::multiply::multiply_fp(a, b)
+    }
+}
+
+/// Samples an element uniformly from the whole field, without going through `Bounded`.
+impl<const P: u64> Distribution<Fp<P>> for Standard {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Fp<P> {
+        return Fp { value: rng.gen_range(0, P) }
+    }
+}
+
+/// Identity tests draw from the whole field, so a trial misses with probability at most d/P.
+impl<const P: u64> RandomFieldElement for Fp<P> {
+    type SampleSet = WholeField<Self>;
+
+    fn sample_set() -> WholeField<Self> {
+        return WholeField::new(P as f64)
+    }
+}
+
+impl<const P: u64> FieldInfo for Fp<P> {
+    fn characteristic() -> u64 {
+        return P
+    }
+
+    fn cardinality() -> Cardinality {
+        let _: () = Self::VALID_MODULUS;
+        return Cardinality::Finite { characteristic: P, degree: 1 }
+    }
+
+    fn elements() -> Option<Vec<Self>> {
+        if P > ENUMERATION_LIMIT {
+            return None
+        }
+        return Some((0..P).map(|value| Self { value }).collect())
+    }
+}
+
+/// UniformFp samples elements of GF(P) whose canonical representatives lie in a range.
+#[derive(Clone, Copy, Debug)]
+pub struct UniformFp<const P: u64>(UniformInt<u64>);
+
+impl<const P: u64> UniformSampler for UniformFp<P> {
+    type X = Fp<P>;
+
+    fn new<B1, B2>(low: B1, high: B2) -> Self where
+        B1: SampleBorrow<Self::X> + Sized,
+        B2: SampleBorrow<Self::X> + Sized {
+        return UniformFp(UniformInt::<u64>::new(low.borrow().value, high.borrow().value))
+    }
+
+    fn new_inclusive<B1, B2>(low: B1, high: B2) -> Self where
+        B1: SampleBorrow<Self::X> + Sized,
+        B2: SampleBorrow<Self::X> + Sized {
+        return UniformFp(UniformInt::<u64>::new_inclusive(low.borrow().value, high.borrow().value))
+    }
+
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Self::X {
+        return Fp { value: self.0.sample(rng) }
+    }
+}
+
+impl<const P: u64> SampleUniform for Fp<P> {
+    type Sampler = UniformFp<P>;
+}
+
+thread_local! {
+    /// The modulus of the field that the `FieldInfo` and `RandomFieldElement` impls of DynFp
+    /// describe, or zero outside of `DynFp::with_field`.
+    static FIELD_MODULUS: Cell<u64> = const { Cell::new(0) };
+}
+
+/// Restores the previous field of `DynFp::with_field` when dropped, so a panic doesn't leak it.
+struct RestoreFieldModulus(u64);
+
+impl Drop for RestoreFieldModulus {
+    fn drop(&mut self) {
+        FIELD_MODULUS.with(|m| m.set(self.0));
+    }
+}
+
+/// DynFp is an element of a prime field GF(p) where the modulus is chosen at runtime.
+///
+/// Every element carries its modulus. The identities returned by `zero()` and `one()` (and
+/// anything computed only from them) don't know the modulus yet, so they are stored as plain
+/// integers and take on the modulus of the first element they are combined with. Combining
+/// elements with two different moduli panics.
+#[derive(Debug, Clone, Copy)]
+pub struct DynFp {
+    value: u64,
+    // zero means the modulus is not known yet, and value holds the bits of an i64.
+    modulus: u64,
+}
+
+impl DynFp {
+    /// Creates the element of GF(modulus) that is congruent to value. The modulus must be a
+    /// prime smaller than 2^63.
+    pub fn new(value: u64, modulus: u64) -> Self {
+        assert!((2..1 << 63).contains(&modulus), "modulus {} is out of range", modulus);
+        debug_assert!(crate::primality::is_prime(modulus), "{} is not a prime modulus", modulus);
+        return Self { value: value % modulus, modulus }
+    }
+
+    /// Samples an element uniformly at random from GF(modulus).
+    pub fn random<R: Rng + ?Sized>(modulus: u64, rng: &mut R) -> Self {
+        return Self::new(rng.gen_range(0, modulus), modulus)
+    }
+
+    /// Runs f with GF(modulus) as the field that `FieldInfo` and `RandomFieldElement` describe
+    /// for DynFp, on this thread. Those traits have no element to read the modulus from, so
+    /// this is what lets polynomials over DynFp be identity tested:
+    ///
+    /// ```
+    /// use cs225_impl::{IdentityTest, VecPoly};
+    /// use cs225_impl::field::DynFp;
+    ///
+    /// let poly = VecPoly::new(vec![DynFp::new(1, 101), DynFp::new(3, 101)]);
+    /// assert!(!DynFp::with_field(101, || poly.probably_zero()));
+    /// ```
+    ///
+    /// The previous field is restored afterwards, so calls can be nested. Identity tests panic if
+    /// a coefficient belongs to a different field than the one set here.
+    pub fn with_field<O, F: FnOnce() -> O>(modulus: u64, f: F) -> O {
+        assert!((2..1 << 63).contains(&modulus), "modulus {} is out of range", modulus);
+        debug_assert!(crate::primality::is_prime(modulus), "{} is not a prime modulus", modulus);
+        let _restore = RestoreFieldModulus(FIELD_MODULUS.with(|m| m.replace(modulus)));
+        return f()
+    }
+
+    /// Returns the modulus set by `with_field`. Panics outside of it.
+    fn field_modulus() -> u64 {
+        let modulus = FIELD_MODULUS.with(Cell::get);
+        assert!(modulus != 0, "DynFp only has a field size inside DynFp::with_field");
+        return modulus
+    }
+
+    /// Returns the canonical representative of the element. For elements whose modulus is not
+    /// known yet, this returns None unless the value is nonnegative.
+    pub fn value(&self) -> Option<u64> {
+        if self.modulus == 0 && (self.value as i64) < 0 {
+            return None
+        }
+        return Some(self.value)
+    }
+
+    /// Returns the modulus of the element, or None if it is not known yet.
+    pub fn modulus(&self) -> Option<u64> {
+        if self.modulus == 0 {
+            return None
+        }
+        return Some(self.modulus)
+    }
+
+    /// Raises the element to the power exp using repeated squaring.
+    pub fn pow(self, mut exp: u64) -> Self {
+        let mut result = Self::one();
+        let mut square = self;
+        while exp > 0 {
+            if exp & 1 == 1 {
+                result = result * square;
+            }
+            square = square * square;
+            exp >>= 1;
+        }
+        return result
+    }
+
+    /// Returns the multiplicative inverse of the element, or None if the element is zero or is
+    /// an integer other than +-1 whose modulus is not known yet.
+    pub fn inverse(self) -> Option<Self> {
+        if self.modulus == 0 {
+            return match self.value as i64 {
+                1 | -1 => Some(self),
+                _ => None,
+            }
+        }
+        if self.value == 0 {
+            return None
+        }
+        return Some(Self { value: pow_mod(self.value, self.modulus - 2, self.modulus), modulus: self.modulus })
+    }
+
+    fn unresolved(value: i64) -> Self {
+        return Self { value: value as u64, modulus: 0 }
+    }
+
+    /// Returns the canonical representative of the element modulo m.
+    fn resolve(&self, m: u64) -> u64 {
+        if self.modulus == 0 {
+            return (self.value as i64 as i128).rem_euclid(m as i128) as u64
+        }
+        return self.value
+    }
+
+    /// Returns the modulus shared by both elements, or zero if neither knows its modulus.
+    fn common_modulus(&self, other: &Self) -> u64 {
+        match (self.modulus, other.modulus) {
+            (0, m) | (m, 0) => return m,
+            (m, n) => {
+                assert_eq!(m, n, "cannot combine elements of GF({}) and GF({})", m, n);
+                return m
+            }
+        }
+    }
+
+    fn combine(self, other: Self, op: fn(u64, u64, u64) -> u64, unresolved_op: fn(i64, i64) -> Option<i64>) -> Self {
+        let m = self.common_modulus(&other);
+        if m == 0 {
+            let value = unresolved_op(self.value as i64, other.value as i64).expect("overflow in a DynFp constant without a modulus");
+            return Self::unresolved(value)
+        }
+        return Self { value: op(self.resolve(m), other.resolve(m), m), modulus: m }
+    }
+}
+
+impl fmt::Display for DynFp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.modulus == 0 {
+            return write!(f, "{}", self.value as i64)
+        }
+        return write!(f, "{} (mod {})", self.value, self.modulus)
+    }
+}
+
+impl PartialEq for DynFp {
+    fn eq(&self, other: &Self) -> bool {
+        match (self.modulus, other.modulus) {
+            (0, 0) => return self.value == other.value,
+            (0, m) | (m, 0) => return self.resolve(m) == other.resolve(m),
+            (m, n) => return m == n && self.value == other.value,
+        }
+    }
+}
+
+impl Add for DynFp {
+    type Output=Self;
+
+    fn add(self, other: Self) -> Self {
+        return self.combine(other, add_mod, i64::checked_add)
+    }
+}
+
+impl Sub for DynFp {
+    type Output=Self;
+
+    fn sub(self, other: Self) -> Self {
+        return self.combine(other, sub_mod, i64::checked_sub)
+    }
+}
+
+impl Mul for DynFp {
+    type Output=Self;
+
+    fn mul(self, other: Self) -> Self {
+        return self.combine(other, mul_mod, i64::checked_mul)
+    }
+}
+
+impl Div for DynFp {
+    type Output=Self;
+
+    /// Divides two field elements. Panics if other is zero.
+    fn div(self, other: Self) -> Self {
+        let m = self.common_modulus(&other);
+        let divisor = if m == 0 { other } else { Self::new(other.resolve(m), m) };
+        let inverse = divisor.inverse().expect("division by zero in GF(p)");
+        return self.combine(inverse, mul_mod, i64::checked_mul)
+    }
+}
+
+impl Neg for DynFp {
+    type Output=Self;
+
+    fn neg(self) -> Self {
+        return Self::zero() - self
+    }
+}
+
+impl Zero for DynFp {
+    fn zero() -> Self {
+        return Self::unresolved(0)
+    }
+
+    fn is_zero(&self) -> bool {
+        return self.value == 0
+    }
+}
+
+impl One for DynFp {
+    fn one() -> Self {
+        return Self::unresolved(1)
+    }
+}
+
+impl AbstractMagma<Additive> for DynFp {
+    fn operate(&self, right: &Self) -> Self {
+        return *self + *right
+    }
+}
+
+impl AbstractMagma<Multiplicative> for DynFp {
+    fn operate(&self, right: &Self) -> Self {
+        return *self * *right
+    }
+}
+
+impl Identity<Additive> for DynFp {
+    fn identity() -> Self {
+        return Self::zero()
+    }
+}
+
+impl Identity<Multiplicative> for DynFp {
+    fn identity() -> Self {
+        return Self::one()
+    }
+}
+
+impl TwoSidedInverse<Additive> for DynFp {
+    fn two_sided_inverse(&self) -> Self {
+        return -*self
+    }
+}
+
+impl TwoSidedInverse<Multiplicative> for DynFp {
+    /// Returns the multiplicative inverse. Panics if the element is not invertible.
+    fn two_sided_inverse(&self) -> Self {
+        return self.inverse().expect("element has no multiplicative inverse")
+    }
+}
+
+impl_abstract_field!([] DynFp);
+
+impl IntegralDomain for DynFp {}
+impl Field for DynFp {}
+
+/// Samples an element uniformly from the field set by `DynFp::with_field`.
+impl Distribution<DynFp> for Standard {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> DynFp {
+        return DynFp::random(DynFp::field_modulus(), rng)
+    }
+}
+
+/// Identity tests draw from the whole field set by `DynFp::with_field`, and panic outside of it.
+impl RandomFieldElement for DynFp {
+    type SampleSet = WholeField<Self>;
+
+    fn sample_set() -> WholeField<Self> {
+        return WholeField::new(Self::field_modulus() as f64)
+    }
+}
+
+/// The field is the one set by `DynFp::with_field`. These panic outside of it.
+impl FieldInfo for DynFp {
+    fn characteristic() -> u64 {
+        return Self::field_modulus()
+    }
+
+    fn cardinality() -> Cardinality {
+        return Cardinality::Finite { characteristic: Self::field_modulus(), degree: 1 }
+    }
+
+    fn elements() -> Option<Vec<Self>> {
+        let modulus = Self::field_modulus();
+        if modulus > ENUMERATION_LIMIT {
+            return None
+        }
+        return Some((0..modulus).map(|value| Self::new(value, modulus)).collect())
+    }
+
+    /// Constants whose modulus is not known yet belong to every field.
+    fn contains(element: &Self) -> bool {
+        return element.modulus == 0 || element.modulus == Self::field_modulus()
+    }
+}
+
+/// UniformDynFp samples elements of GF(p) whose canonical representatives lie in a range. At
+/// least one end of the range must know its modulus.
+#[derive(Clone, Copy, Debug)]
+pub struct UniformDynFp {
+    inner: UniformInt<u64>,
+    modulus: u64,
+}
+
+impl UniformSampler for UniformDynFp {
+    type X = DynFp;
+
+    fn new<B1, B2>(low: B1, high: B2) -> Self where
+        B1: SampleBorrow<Self::X> + Sized,
+        B2: SampleBorrow<Self::X> + Sized {
+        let (low, high) = (low.borrow(), high.borrow());
+        let modulus = low.common_modulus(high);
+        assert!(modulus != 0, "cannot sample a DynFp without knowing the modulus");
+        return UniformDynFp { inner: UniformInt::<u64>::new(low.resolve(modulus), high.resolve(modulus)), modulus }
+    }
+
+    fn new_inclusive<B1, B2>(low: B1, high: B2) -> Self where
+        B1: SampleBorrow<Self::X> + Sized,
+        B2: SampleBorrow<Self::X> + Sized {
+        let (low, high) = (low.borrow(), high.borrow());
+        let modulus = low.common_modulus(high);
+        assert!(modulus != 0, "cannot sample a DynFp without knowing the modulus");
+        return UniformDynFp { inner: UniformInt::<u64>::new_inclusive(low.resolve(modulus), high.resolve(modulus)), modulus }
+    }
+
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Self::X {
+        return DynFp { value: self.inner.sample(rng), modulus: self.modulus }
+    }
+}
+
+impl SampleUniform for DynFp {
+    type Sampler = UniformDynFp;
+}
+
+#[cfg(test)]
+fn random_elements<const P: u64>(count: usize) -> Vec<Fp<P>> {
+    let mut rng = StdRng::seed_from_u64(P);
+    let mut elements = vec![Fp::zero(), Fp::one(), -Fp::one()];
+    elements.extend((0..count).map(|_| rng.gen::<Fp<P>>()));
+    return elements
+}
+
+#[test]
+fn fp_field_axioms() {
+    use super::check_field_axioms;
+
+    check_field_axioms(&random_elements::<2>(4));
+    check_field_axioms(&random_elements::<3>(4));
+    check_field_axioms(&random_elements::<101>(10));
+    check_field_axioms(&random_elements::<65537>(10));
+    check_field_axioms(&random_elements::<2147483647>(10));
+    check_field_axioms(&random_elements::<2305843009213693951>(10));
+}
+
+#[test]
+fn fp_inverses_and_fermat() {
+    for value in 1..101 {
+        let a = Fp::<101>::new(value);
+        assert_eq!(a * a.inverse().unwrap(), Fp::one());
+        assert_eq!(a.pow(100), Fp::one());
+    }
+    assert_eq!(Fp::<101>::zero().inverse(), None);
+    assert_eq!(Fp::<101>::from_i64(-1), Fp::new(100));
+}
+
+#[test]
+fn dynfp_field_axioms() {
+    use super::check_field_axioms;
+
+    let mut rng = StdRng::seed_from_u64(225);
+    for &modulus in &[2, 7, 65537, 2305843009213693951] {
+        let mut elements = vec![DynFp::zero(), DynFp::one(), -DynFp::one()];
+        elements.extend((0..8).map(|_| DynFp::random(modulus, &mut rng)));
+        check_field_axioms(&elements);
+    }
+}
+
+#[test]
+fn dynfp_constants_take_on_modulus() {
+    let two = DynFp::one() + DynFp::one();
+    assert_eq!(two.modulus(), None);
+    assert_eq!(two * DynFp::new(4, 7), DynFp::new(1, 7));
+    assert_eq!(-DynFp::one() + DynFp::new(0, 13), DynFp::new(12, 13));
+    assert_eq!(DynFp::new(3, 7) / two, DynFp::new(5, 7));
+    assert_ne!(DynFp::new(3, 7), DynFp::new(3, 11));
+}
+
+#[test]
+#[should_panic]
+fn dynfp_mismatched_moduli() {
+    let _ = DynFp::new(1, 7) + DynFp::new(1, 11);
+}
+
+#[test]
+fn fp_pit_zero() {
+    use crate::{IdentityTest, VecPoly};
+
+    let zero_poly = VecPoly::new(vec![Fp::<103>::zero(); 5]);
+    assert_eq!(zero_poly.probably_zero(), true);
+    let zero_poly = VecPoly::new(vec![Fp::<2147483647>::zero(); 5]);
+    assert_eq!(zero_poly.probably_zero(), true);
+}
+
+#[test]
+fn fp_pit_nonzero() {
+    use crate::{IdentityTest, VecPoly};
+
+    // x^2 + 1 has no roots when p = 3 mod 4, so every evaluation is nonzero.
+    let nonzero_poly = VecPoly::new(vec![Fp::<103>::one(), Fp::zero(), Fp::one()]);
+    assert_eq!(nonzero_poly.probably_zero(), false);
+    let nonzero_poly = VecPoly::new(vec![Fp::<2147483647>::one(), Fp::zero(), Fp::one()]);
+    assert_eq!(nonzero_poly.probably_zero(), false);
+    let nonzero_poly = VecPoly::new(vec![Fp::<2305843009213693951>::one(), Fp::zero(), Fp::one()]);
+    assert_eq!(nonzero_poly.probably_zero(), false);
+}
+
+#[test]
+fn fp_pit_equality() {
+    use crate::VecPoly;
+
+    // (x + 1)^2 = x^2 + 2x + 1
+    let expanded = VecPoly::new(vec![Fp::<65537>::new(1), Fp::new(2), Fp::new(1)]);
+    let summed = VecPoly::new(vec![Fp::new(1), Fp::new(1), Fp::new(0)]) + VecPoly::new(vec![Fp::new(0), Fp::new(1), Fp::new(1)]);
+    assert!(expanded == summed);
+    let different = VecPoly::new(vec![Fp::<103>::new(1), Fp::new(2), Fp::new(1)]);
+    assert!(different != VecPoly::new(vec![Fp::new(1), Fp::new(2), Fp::new(3)]));
+}
+
+#[test]
+fn dynfp_polynomials_are_identity_tested_inside_a_field() {
+    use crate::{IdentityTest, ProbablyEq, VecPoly, Verdict};
+    use crate::field::FieldInfo;
+
+    let mut rng = StdRng::seed_from_u64(43);
+    // (x + 1)^2 and x^2 + 2x + 1 over GF(101), built from elements that carry their modulus
+    let f = |c: u64| DynFp::new(c, 101);
+    let squared = &VecPoly::new(vec![f(1), f(1)]) * &VecPoly::new(vec![f(1), f(1)]);
+    let expanded = VecPoly::new(vec![f(1), f(2), f(1)]);
+    DynFp::with_field(101, || {
+        assert_eq!(DynFp::cardinality(), Cardinality::Finite { characteristic: 101, degree: 1 });
+        assert_eq!(DynFp::elements().map(|e| e.len()), Some(101));
+        assert!(squared.probably_eq_with_rng(&expanded, 1e-9, &mut rng));
+        match (&squared - &VecPoly::new(vec![f(1), f(0), f(1)])).test_zero_with_rng(1e-9, &mut rng) {
+            Verdict::NonZero { witness, value } => assert_eq!(value, f(2) * witness[0]),
+            verdict => panic!("unexpected verdict {:?}", verdict),
+        }
+        // a nested field takes over until it returns
+        assert_eq!(DynFp::with_field(65537, DynFp::characteristic), 65537);
+        assert_eq!(DynFp::characteristic(), 101);
+    });
+}
+
+#[test]
+#[should_panic(expected = "DynFp::with_field")]
+fn dynfp_has_no_field_size_outside_with_field() {
+    use crate::field::FieldInfo;
+
+    let _ = DynFp::cardinality();
+}
+
+#[test]
+#[should_panic(expected = "the coefficients are not in the field being tested over")]
+fn dynfp_polynomials_are_only_tested_in_their_own_field() {
+    use crate::{IdentityTest, VecPoly};
+
+    let poly = VecPoly::new(vec![DynFp::new(1, 101), DynFp::new(3, 101)]);
+    DynFp::with_field(65537, || poly.probably_zero());
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic(expected = "15 is not a prime modulus")]
+fn dynfp_rejects_composite_moduli() {
+    let _ = DynFp::new(1, 15);
+}
@@ -0,0 +1,176 @@
+//! Identity testing for polynomials with integer coefficients.
+//!
+//! Evaluating an integer polynomial at a random point overflows machine integers almost
+//! immediately, and makes big integers grow with the degree. Instead the polynomial is reduced
+//! modulo a random prime p and tested over GF(p), where every number fits in a u64. This is
+//! wrong in two ways, which are both bounded: p can divide every coefficient, and the random
+//! point can be a root of the reduced polynomial.
+
+use rand::prelude::*;
+use num::{BigInt, Integer, Signed, ToPrimitive};
+use crate::{Polynomial, VecPoly, Verdict};
+use crate::field::DynFp;
+use crate::primality::is_prime;
+
+/// ReduceModPrime is implemented by integer types, so their polynomials can be fingerprinted.
+pub trait ReduceModPrime {
+    /// Returns the residue of the integer modulo p, in [0, p).
+    fn residue(&self, p: u64) -> u64;
+
+    /// Returns the number of bits of the absolute value of the integer.
+    fn bits(&self) -> u64;
+}
+
+macro_rules! impl_reduce_mod_prime {
+    ($($t:ty),*) => {
+        $(impl ReduceModPrime for $t {
+            fn residue(&self, p: u64) -> u64 {
+                return (*self as i128).rem_euclid(p as i128) as u64
+            }
+
+            fn bits(&self) -> u64 {
+                return 128 - (*self as i128).unsigned_abs().leading_zeros() as u64
+            }
+        })*
+    };
+}
+
+impl_reduce_mod_prime!(i8, i16, i32, i64, isize);
+
+impl ReduceModPrime for i128 {
+    fn residue(&self, p: u64) -> u64 {
+        return self.rem_euclid(p as i128) as u64
+    }
+
+    fn bits(&self) -> u64 {
+        return 128 - self.unsigned_abs().leading_zeros() as u64
+    }
+}
+
+impl ReduceModPrime for BigInt {
+    fn residue(&self, p: u64) -> u64 {
+        return self.mod_floor(&BigInt::from(p)).to_u64().expect("a residue modulo a u64 fits in a u64")
+    }
+
+    fn bits(&self) -> u64 {
+        return self.abs().bits()
+    }
+}
+
+/// Returns a uniformly random prime with exactly bits bits, so in [2^(bits-1), 2^bits).
+///
+/// Random odd numbers of that size are tried until one is prime, except that 2 and 3 are the two
+/// primes with two bits and are drawn directly. By the prime number theorem about one in
+/// bits * ln(2) / 2 of the odd numbers is prime, so this takes O(bits) attempts on average.
+/// Panics unless 2 <= bits <= 63.
+pub fn random_prime<R: Rng + ?Sized>(bits: u32, rng: &mut R) -> u64 {
+    assert!((2..=63).contains(&bits), "primes must have between 2 and 63 bits, not {}", bits);
+    if bits == 2 {
+        // 2 is the only even prime, which the odd candidates below would never reach
+        return rng.gen_range(2, 4)
+    }
+    let low = 1u64 << (bits - 1);
+    loop {
+        let candidate = rng.gen_range(low, low << 1) | 1;
+        if is_prime(candidate) {
+            return candidate
+        }
+    }
+}
+
+/// Returns a lower bound on the number of primes with exactly bits bits, from the bounds
+/// x/ln(x) < pi(x) < 1.25506 x/ln(x) for x >= 17.
+fn primes_with_bits(bits: u32) -> f64 {
+    let high = 2f64.powi(bits as i32);
+    let low = high / 2.0;
+    return high / high.ln() - 1.25506 * low / low.ln()
+}
+
+impl<T: ReduceModPrime> VecPoly<T> {
+    /// Returns the polynomial with every coefficient reduced modulo the prime p.
+    pub fn reduce_mod(&self, p: u64) -> VecPoly<DynFp> {
+        return VecPoly::from_lowest_first(self.coefficients.iter().map(|c| DynFp::new(c.residue(p), p)).collect())
+    }
+
+    /// Tests whether the polynomial is zero by reducing it modulo random primes with prime_bits
+    /// bits and evaluating it at a random point of GF(p). A nonzero verdict carries the point and
+    /// value in GF(p), so it can be checked again with `reduce_mod`.
+    ///
+    /// A trial misses a nonzero polynomial of degree d if p divides its leading coefficient c,
+    /// which at most log(|c|)/(prime_bits - 1) of the primes do, or if the point is a root of the
+    /// reduced polynomial, which happens with probability at most d/p. Returns None if these add
+    /// up to one or more, in which case more prime bits are needed. Panics unless
+    /// 16 <= prime_bits <= 62.
+    pub fn test_zero_mod_primes_with_rng<R: Rng + ?Sized>(&self, prime_bits: u32, error: f64, rng: &mut R) -> Option<Verdict<DynFp>> {
+        assert!((16..=62).contains(&prime_bits), "prime_bits must be in [16, 62], not {}", prime_bits);
+        assert!(error > 0.0 && error < 1.0, "error must be in (0, 1)");
+        let (degree, lead) = match (self.degree(), self.coefficients.last()) {
+            (Some(degree), Some(lead)) => (degree, lead),
+            _ => return Some(Verdict::ProbablyZero { trials: 1, error_bound: 0.0 }),
+        };
+        let bad_primes = (lead.bits() as f64 / (prime_bits - 1) as f64).floor();
+        let per_trial = bad_primes / primes_with_bits(prime_bits) + degree as f64 / 2f64.powi(prime_bits as i32 - 1);
+        if per_trial >= 1.0 {
+            return None
+        }
+        let trials = if per_trial == 0.0 { 1 } else { ((error.ln() / per_trial.ln()).ceil() as usize).max(1) };
+        for _ in 0..trials {
+            let p = random_prime(prime_bits, rng);
+            let x = DynFp::random(p, rng);
+            if let Some(value) = self.reduce_mod(p).evaluate(x) {
+                if value.value() != Some(0) {
+                    return Some(Verdict::NonZero { witness: vec![x], value })
+                }
+            }
+        }
+        return Some(Verdict::ProbablyZero { trials, error_bound: per_trial.powi(trials as i32) })
+    }
+}
+
+#[test]
+fn random_primes_have_the_requested_size() {
+    let mut rng = StdRng::seed_from_u64(1);
+    for &bits in [2, 5, 16, 31, 62, 63].iter() {
+        for _ in 0..10 {
+            let p = random_prime(bits, &mut rng);
+            assert!(is_prime(p));
+            assert_eq!(64 - p.leading_zeros(), bits);
+        }
+    }
+    let two_bit: Vec<u64> = (0..40).map(|_| random_prime(2, &mut rng)).collect();
+    assert!(two_bit.contains(&2) && two_bit.contains(&3));
+}
+
+#[test]
+fn integer_polynomials_that_overflow_are_tested_exactly() {
+    let mut rng = StdRng::seed_from_u64(2);
+    // 2^62 x^30 - 2^62 overflows an i64 at every point other than -1, 0 and 1
+    let mut coefficients = vec![0i64; 31];
+    coefficients[0] = -(1 << 62);
+    coefficients[30] = 1 << 62;
+    let poly = VecPoly::from_lowest_first(coefficients);
+    match poly.test_zero_mod_primes_with_rng(61, 1e-9, &mut rng) {
+        Some(Verdict::NonZero { witness, value }) => {
+            let p = value.modulus().unwrap();
+            assert_eq!(poly.reduce_mod(p).evaluate(witness[0]).and_then(|v| v.value()), value.value());
+        }
+        verdict => panic!("unexpected verdict {:?}", verdict),
+    }
+    let zero = VecPoly::<i64>::new(vec![0, 0]);
+    assert_eq!(zero.test_zero_mod_primes_with_rng(61, 1e-9, &mut rng), Some(Verdict::ProbablyZero { trials: 1, error_bound: 0.0 }));
+}
+
+#[test]
+fn big_integer_fingerprints_bound_the_error() {
+    let mut rng = StdRng::seed_from_u64(3);
+    // the product of all primes below 100 has many small prime factors, but none of 61 bits
+    let primorial = (2u64..100).filter(|&n| is_prime(n)).fold(BigInt::from(1), |acc, p| acc * p);
+    let poly = VecPoly::from_lowest_first(vec![BigInt::from(0), primorial.clone() * &primorial]);
+    let verdict = poly.test_zero_mod_primes_with_rng(61, 1e-12, &mut rng);
+    assert!(verdict.is_some_and(|v| !v.is_probably_zero()));
+
+    // with 16 bit primes, a degree 40000 polynomial has no useful bound
+    let mut coefficients = vec![BigInt::from(0); 40001];
+    coefficients[40000] = BigInt::from(1);
+    assert_eq!(VecPoly::from_lowest_first(coefficients).test_zero_mod_primes_with_rng(16, 0.01, &mut rng), None);
+}
@@ -0,0 +1,215 @@
+//! Interpolation and multipoint evaluation for VecPoly over a field.
+//!
+//! Given n points (x_i, y_i) with distinct x_i there is exactly one polynomial of degree less
+//! than n through them. `lagrange` and `newton` find it in O(n^2) operations, and a
+//! `SubproductTree` evaluates and interpolates at n points by divide and conquer, which is
+//! quasi-linear when multiplication and division with remainder are.
+
+use crate::{Polynomial, VecPoly};
+use crate::algebra::Field;
+use crate::newton::NewtonPoly;
+use crate::point_value::PointValuePoly;
+
+/// Returns the polynomial of degree less than n through the n points, in the Lagrange form
+/// sum y_i prod_{j != i} (x - x_j) / (x_i - x_j), expanded into coefficients. Panics if two
+/// points share an x coordinate.
+pub fn lagrange<T: Field + PartialEq>(points: &[(T, T)]) -> VecPoly<T> {
+    let (xs, ys) = points.iter().cloned().unzip();
+    return PointValuePoly::new(xs, ys).to_coefficients()
+}
+
+/// Returns the polynomial of degree less than n through the n points in Newton form. Panics if
+/// two points share an x coordinate.
+pub fn newton<T: Field + PartialEq>(points: &[(T, T)]) -> NewtonPoly<T> {
+    let mut poly = NewtonPoly::new();
+    for (x, y) in points.iter() {
+        poly.push(x.clone(), y.clone());
+    }
+    return poly
+}
+
+impl<T: Field> VecPoly<T> {
+    /// Evaluates the polynomial at every point with Horner's method, which takes O(nd)
+    /// operations for n points and degree d. `SubproductTree::evaluate` is faster for many
+    /// points.
+    pub fn evaluate_many(&self, points: &[T]) -> Vec<T> {
+        return points.iter().map(|x| self.evaluate(x.clone()).unwrap_or_else(T::zero)).collect()
+    }
+}
+
+/// SubproductTree is the binary tree of products of the linear factors x - x_i. The leaves are
+/// the factors, every node is the product of its children, and the root is prod (x - x_i).
+///
+/// Reducing a polynomial modulo the nodes on the way down the tree evaluates it at every point,
+/// and combining weighted values on the way up interpolates. Each level costs a constant number
+/// of multiplications and divisions of total degree n, which go through `mul_fast` and
+/// `div_rem_fast`, so the tree takes O(M(n) log n) operations: O(n log^2 n) over an `Fp` with
+/// NTT support, and O(n^1.59 log n) with Karatsuba.
+#[derive(Debug, Clone)]
+pub struct SubproductTree<T> {
+    // levels[0] holds the leaves and the last level holds the root
+    levels: Vec<Vec<VecPoly<T>>>,
+}
+
+impl<T: Field + PartialEq> SubproductTree<T> {
+    /// Builds the tree over the points. Panics if there are no points.
+    pub fn new(points: &[T]) -> Self {
+        assert!(!points.is_empty(), "a subproduct tree needs at least one point");
+        let leaves: Vec<VecPoly<T>> = points.iter().map(|x| VecPoly::from_lowest_first(vec![T::zero() - x.clone(), T::one()])).collect();
+        let mut levels = vec![leaves];
+        while levels.last().is_some_and(|level| level.len() > 1) {
+            let next = levels.last().unwrap().chunks(2).map(|pair| match pair {
+                [left, right] => left.mul_fast(right),
+                [single] => single.clone(),
+                _ => unreachable!(),
+            }).collect();
+            levels.push(next);
+        }
+        return Self { levels }
+    }
+
+    /// Returns the product of all the linear factors, prod (x - x_i).
+    pub fn root(&self) -> &VecPoly<T> {
+        return &self.levels.last().unwrap()[0]
+    }
+
+    /// Returns the points the tree was built over.
+    pub fn points(&self) -> Vec<T> {
+        return self.levels[0].iter().map(|leaf| T::zero() - leaf.coefficients()[0].clone()).collect()
+    }
+
+    /// Evaluates poly at every point by reducing it modulo each node of the tree, from the root
+    /// down. The remainder at the leaf x - x_i is the constant poly(x_i).
+    pub fn evaluate(&self, poly: &VecPoly<T>) -> Vec<T> {
+        let mut remainders = vec![poly.div_rem_fast(self.root()).1];
+        for level in self.levels.iter().rev().skip(1) {
+            remainders = level.iter().enumerate().map(|(i, node)| remainders[i / 2].div_rem_fast(node).1).collect();
+        }
+        return remainders.iter().map(|r| r.coefficients().first().cloned().unwrap_or_else(T::zero)).collect()
+    }
+
+    /// Returns the polynomial of degree less than n that takes the given values at the points.
+    ///
+    /// With m = prod (x - x_i), the Lagrange basis polynomial of x_i is m / ((x - x_i) m'(x_i)).
+    /// The weights y_i / m'(x_i) come from one multipoint evaluation of m', and the sum of the
+    /// weighted basis polynomials is built up the tree as left * right' + right * left'.
+    pub fn interpolate(&self, values: &[T]) -> VecPoly<T> {
+        assert_eq!(values.len(), self.levels[0].len(), "every point needs exactly one value");
+        let derivatives = self.evaluate(&self.root().derivative());
+        let mut combined: Vec<VecPoly<T>> = values.iter().zip(derivatives).map(|(y, d)| VecPoly::from_lowest_first(vec![y.clone() / d])).collect();
+        for level in self.levels.iter().take(self.levels.len() - 1) {
+            combined = level.chunks(2).zip(combined.chunks(2)).map(|(nodes, sums)| match (nodes, sums) {
+                ([left, right], [left_sum, right_sum]) => &left_sum.mul_fast(right) + &right_sum.mul_fast(left),
+                (_, [single]) => single.clone(),
+                _ => unreachable!(),
+            }).collect();
+        }
+        return combined.pop().unwrap()
+    }
+}
+
+#[cfg(test)]
+fn random_points<R: rand::Rng>(n: usize, rng: &mut R) -> Vec<(crate::field::Fp<65537>, crate::field::Fp<65537>)> {
+    use crate::field::Fp;
+
+    // distinct x coordinates, shuffled so the tree isn't built over sorted points
+    let mut xs: Vec<u64> = (0..65537).step_by(65537 / n.max(1)).take(n).collect();
+    rand::seq::SliceRandom::shuffle(xs.as_mut_slice(), rng);
+    return xs.into_iter().map(|x| (Fp::new(x), rng.gen())).collect()
+}
+
+#[test]
+fn interpolation_methods_agree() {
+    use rand::prelude::*;
+
+    let mut rng = StdRng::seed_from_u64(21);
+    for &n in [1usize, 2, 3, 8, 33].iter() {
+        let points = random_points(n, &mut rng);
+        let poly = lagrange(&points);
+        assert!(poly.degree().is_none_or(|d| d < n));
+        for (x, y) in points.iter() {
+            assert_eq!(poly.evaluate_many(&[*x]), vec![*y]);
+        }
+        let newton_form = newton(&points);
+        assert_eq!(newton_form.to_coefficients(), poly);
+        let x = rng.gen();
+        assert_eq!(newton_form.evaluate(x), poly.evaluate(x));
+        let xs: Vec<_> = points.iter().map(|(x, _)| *x).collect();
+        let ys: Vec<_> = points.iter().map(|(_, y)| *y).collect();
+        let tree = SubproductTree::new(&xs);
+        assert_eq!(tree.points(), xs);
+        assert_eq!(tree.interpolate(&ys), poly);
+    }
+}
+
+#[test]
+fn multipoint_evaluation_matches_horner() {
+    use rand::prelude::*;
+    use crate::field::Fp;
+
+    let mut rng = StdRng::seed_from_u64(22);
+    let poly = VecPoly::from_lowest_first((0..50).map(|_| rng.gen::<Fp<65537>>()).collect());
+    for &n in [1usize, 7, 64, 100].iter() {
+        let xs: Vec<_> = random_points(n, &mut rng).into_iter().map(|(x, _)| x).collect();
+        let tree = SubproductTree::new(&xs);
+        assert_eq!(tree.evaluate(&poly), poly.evaluate_many(&xs));
+        assert!(tree.root().evaluate_many(&xs).iter().all(|v| v.value() == 0));
+    }
+}
+
+#[test]
+fn subproduct_tree_takes_the_fast_path_for_many_points() {
+    use rand::prelude::*;
+    use crate::field::Fp;
+    type F = Fp<998244353>;
+
+    let mut rng = StdRng::seed_from_u64(46);
+    // 1024 points put 512 coefficients in each child of the root, well above the thresholds of
+    // the NTT and of fast division
+    let xs: Vec<F> = (0..1024).map(|i| F::new(i * 7919 + 3)).collect();
+    let poly = VecPoly::from_lowest_first((0..1500).map(|_| rng.gen::<F>()).collect());
+    let tree = SubproductTree::new(&xs);
+    let values = tree.evaluate(&poly);
+    assert_eq!(values, poly.evaluate_many(&xs));
+    let interpolated = tree.interpolate(&values);
+    assert_eq!(interpolated, poly.div_rem(tree.root()).1);
+}
+
+#[test]
+fn newton_form_extends_one_point_at_a_time() {
+    use num::BigRational;
+
+    let q = |n: i64| BigRational::from_integer(n.into());
+    // the squares 0, 1, 4, 9 are fit by x^2, and adding a point off the parabola raises the
+    // degree without changing the existing coefficients
+    let mut poly = newton(&[(q(0), q(0)), (q(1), q(1)), (q(2), q(4)), (q(3), q(9))]);
+    assert_eq!(poly.degree(), Some(2));
+    assert_eq!(poly.to_coefficients(), VecPoly::new(vec![q(1), q(0), q(0)]));
+    let before = poly.coefficients().to_vec();
+    poly.push(q(4), q(17));
+    assert_eq!(&poly.coefficients()[..4], &before[..]);
+    assert_eq!(poly.degree(), Some(4));
+    assert_eq!(poly.evaluate(q(4)), Some(q(17)));
+    assert_eq!(NewtonPoly::<BigRational>::new().evaluate(q(1)), None);
+}
+
+#[test]
+fn shamir_secret_sharing_round_trip() {
+    use rand::prelude::*;
+    use crate::field::Fp;
+    type F = Fp<2147483647>;
+
+    let mut rng = StdRng::seed_from_u64(23);
+    // a random polynomial of degree 2 hides the secret as its constant term; any 3 of the 5
+    // shares recover it, and the evaluations of the shares are a Reed-Solomon codeword
+    let secret = F::new(123456789);
+    let mut coefficients = vec![secret];
+    coefficients.extend((0..2).map(|_| rng.gen::<F>()));
+    let dealer = VecPoly::from_lowest_first(coefficients);
+    let xs: Vec<F> = (1..=5).map(F::new).collect();
+    let shares: Vec<(F, F)> = xs.iter().cloned().zip(SubproductTree::new(&xs).evaluate(&dealer)).collect();
+    for subset in [[0, 1, 2], [0, 2, 4], [1, 3, 4]].iter() {
+        let chosen: Vec<(F, F)> = subset.iter().map(|&i| shares[i]).collect();
+        assert_eq!(lagrange(&chosen).evaluate(F::new(0)), Some(secret));
+    }
+}
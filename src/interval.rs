@@ -0,0 +1,235 @@
+//! Interval arithmetic for evaluating float polynomials with guaranteed error bounds.
+//!
+//! A float evaluation of a polynomial is off by an unknown amount of rounding, and can overflow
+//! to infinity or NaN, so comparing it with zero says little. Evaluating with intervals instead
+//! gives an enclosure that is certain to contain the exact value of the polynomial, treating the
+//! coefficients and the point as exact. Every operation rounds its lower bound down and its upper
+//! bound up by one ulp, which is enough since IEEE operations are correctly rounded.
+
+use rand::prelude::*;
+use num::Float;
+use std::ops::{Add, Mul};
+use crate::{VecPoly, pit};
+use crate::sample::{RandomFieldElement, SampleSet};
+
+/// RoundOutward is implemented by the float types that intervals can be built from.
+pub trait RoundOutward: Float {
+    /// Returns the smallest float larger than self.
+    fn round_up(self) -> Self;
+
+    /// Returns the largest float smaller than self.
+    fn round_down(self) -> Self;
+}
+
+impl RoundOutward for f64 {
+    fn round_up(self) -> Self {
+        return self.next_up()
+    }
+
+    fn round_down(self) -> Self {
+        return self.next_down()
+    }
+}
+
+impl RoundOutward for f32 {
+    fn round_up(self) -> Self {
+        return self.next_up()
+    }
+
+    fn round_down(self) -> Self {
+        return self.next_down()
+    }
+}
+
+/// Interval is the closed interval [low, high] of reals. Overflow widens it to infinity rather
+/// than losing the enclosure, and NaN widens it to the whole real line.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Interval<T> {
+    low: T,
+    high: T,
+}
+
+impl<T: RoundOutward> Interval<T> {
+    /// Creates the interval [low, high]. Panics if low > high or either bound is NaN.
+    pub fn new(low: T, high: T) -> Self {
+        assert!(low <= high, "the interval is empty or has a NaN bound");
+        return Self { low, high }
+    }
+
+    /// Creates the interval that contains only x.
+    pub fn point(x: T) -> Self {
+        return Self::new(x, x)
+    }
+
+    /// Returns the lower bound.
+    pub fn low(&self) -> T {
+        return self.low
+    }
+
+    /// Returns the upper bound.
+    pub fn high(&self) -> T {
+        return self.high
+    }
+
+    /// Returns whether x is in the interval.
+    pub fn contains(&self, x: T) -> bool {
+        return self.low <= x && x <= self.high
+    }
+
+    /// Returns whether every element of the interval is in other.
+    pub fn is_subset_of(&self, other: &Self) -> bool {
+        return other.low <= self.low && self.high <= other.high
+    }
+
+    /// Returns whether the interval and other have no element in common.
+    pub fn is_disjoint_from(&self, other: &Self) -> bool {
+        return self.high < other.low || other.high < self.low
+    }
+
+    /// Rounds the bounds of an exactly computed interval outwards, replacing NaN bounds, which
+    /// come from 0 * inf or inf - inf, by infinities.
+    fn outward(low: T, high: T) -> Self {
+        let low = if low.is_nan() { T::neg_infinity() } else { low.round_down() };
+        let high = if high.is_nan() { T::infinity() } else { high.round_up() };
+        return Self { low, high }
+    }
+}
+
+impl<T: RoundOutward> Add for Interval<T> {
+    type Output=Self;
+
+    fn add(self, other: Self) -> Self {
+        return Self::outward(self.low + other.low, self.high + other.high)
+    }
+}
+
+impl<T: RoundOutward> Mul for Interval<T> {
+    type Output=Self;
+
+    /// The product of intervals has its extremes at products of the bounds.
+    fn mul(self, other: Self) -> Self {
+        let products = [self.low * other.low, self.low * other.high, self.high * other.low, self.high * other.high];
+        if products.iter().any(|p| p.is_nan()) {
+            return Self::outward(T::nan(), T::nan())
+        }
+        let low = products.iter().copied().fold(T::infinity(), T::min);
+        let high = products.iter().copied().fold(T::neg_infinity(), T::max);
+        return Self::outward(low, high)
+    }
+}
+
+/// FloatVerdict is the outcome of an identity test of a float polynomial with a tolerance.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FloatVerdict<T> {
+    /// Every evaluation was enclosed in [-tolerance, tolerance]. With a tolerance of zero, a
+    /// nonzero polynomial passes with probability at most error_bound.
+    Zero { trials: usize, error_bound: f64 },
+    /// The evaluation at witness was enclosed away from [-tolerance, tolerance], so the
+    /// polynomial is certainly larger than the tolerance there.
+    NonZero { witness: Vec<T>, enclosure: Interval<T> },
+    /// The enclosure of the evaluation at witness overlapped [-tolerance, tolerance] without
+    /// fitting inside it, because of rounding or overflow.
+    Inconclusive { witness: Vec<T>, enclosure: Interval<T> },
+}
+
+impl<T: RoundOutward> VecPoly<T> {
+    /// Returns an enclosure of the exact value of the polynomial at the interval x, using
+    /// Horner's method. When the polynomial has no terms, it returns None.
+    pub fn evaluate_interval(&self, x: Interval<T>) -> Option<Interval<T>> {
+        let mut coefs = self.coefficients.iter().rev();
+        let mut accumulated = Interval::point(*coefs.next()?);
+        for coef in coefs {
+            accumulated = accumulated * x + Interval::point(*coef);
+        }
+        return Some(accumulated)
+    }
+
+    /// Tests whether the polynomial is zero up to tolerance, evaluating it with intervals at
+    /// points drawn from the default sample set of T.
+    ///
+    /// Returns NonZero as soon as an enclosure lies outside [-tolerance, tolerance] and
+    /// Inconclusive as soon as one straddles its edge; otherwise Zero, after enough trials that
+    /// a nonzero polynomial is missed with probability at most error when the tolerance is
+    /// zero. Returns None if the degree is too large for the sample set.
+    pub fn test_zero_with_tolerance_with_rng<R: Rng + ?Sized>(&self, tolerance: T, error: f64, rng: &mut R) -> Option<FloatVerdict<T>> where
+        T: RandomFieldElement {
+        let sample_set = T::sample_set();
+        let degree = match self.degree() {
+            Some(degree) => degree,
+            None => return Some(FloatVerdict::Zero { trials: 1, error_bound: 0.0 }),
+        };
+        let per_trial = degree as f64 / sample_set.size();
+        let trials = pit::trials_for_ratio(per_trial, error)?;
+        let band = Interval::new(-tolerance, tolerance);
+        for _ in 0..trials {
+            let x = sample_set.sample(rng);
+            let enclosure = self.evaluate_interval(Interval::point(x)).expect("the polynomial is nonzero");
+            if enclosure.is_disjoint_from(&band) {
+                return Some(FloatVerdict::NonZero { witness: vec![x], enclosure })
+            }
+            if !enclosure.is_subset_of(&band) {
+                return Some(FloatVerdict::Inconclusive { witness: vec![x], enclosure })
+            }
+        }
+        return Some(FloatVerdict::Zero { trials, error_bound: per_trial.powi(trials as i32) })
+    }
+}
+
+#[test]
+fn interval_horner_encloses_the_exact_value() {
+    use num::BigRational;
+    use crate::Polynomial;
+
+    let mut rng = StdRng::seed_from_u64(17);
+    for _ in 0..50 {
+        let coefficients: Vec<f64> = (0..8).map(|_| rng.gen_range(-10.0, 10.0)).collect();
+        let poly = VecPoly::from_lowest_first(coefficients.clone());
+        let x: f64 = rng.gen_range(-3.0, 3.0);
+        let enclosure = poly.evaluate_interval(Interval::point(x)).unwrap();
+        // the exact value, computed with rationals
+        let exact_poly = VecPoly::from_lowest_first(coefficients.iter().map(|&c| BigRational::from_float(c).unwrap()).collect());
+        let exact = exact_poly.evaluate(BigRational::from_float(x).unwrap()).unwrap();
+        assert!(BigRational::from_float(enclosure.low()).unwrap() <= exact);
+        assert!(exact <= BigRational::from_float(enclosure.high()).unwrap());
+        assert!(enclosure.contains(poly.evaluate(x).unwrap()));
+    }
+}
+
+#[test]
+fn overflow_widens_instead_of_losing_the_enclosure() {
+    // x^400 at 1e3 overflows, and x^400 - x^400 would be inf - inf = NaN
+    let mut coefficients = vec![0.0; 401];
+    coefficients[400] = 1.0;
+    let power = VecPoly::from_lowest_first(coefficients);
+    let enclosure = power.evaluate_interval(Interval::point(1e3)).unwrap();
+    assert_eq!(enclosure.high(), f64::INFINITY);
+    let whole = enclosure * Interval::point(0.0) + Interval::new(-1.0, 1.0);
+    assert!(whole.contains(0.0) && whole.contains(f64::MAX) && whole.contains(f64::MIN));
+    let f32_enclosure = VecPoly::new(vec![1.0f32, 0.0, 0.0]).evaluate_interval(Interval::point(1e30)).unwrap();
+    assert!(f32_enclosure.contains(f32::INFINITY));
+}
+
+#[test]
+fn float_identity_tests_report_three_outcomes() {
+    let mut rng = StdRng::seed_from_u64(18);
+    // (x + 0.1)^2 - (x^2 + 0.2x + 0.01) is rounding noise, below any reasonable tolerance
+    let noise = &VecPoly::new(vec![1.0, 0.1]).pow(2) - &VecPoly::new(vec![1.0, 0.2, 0.01]);
+    assert!(noise.degree().is_some());
+    match noise.test_zero_with_tolerance_with_rng(1e-12, 1e-9, &mut rng) {
+        Some(FloatVerdict::Zero { trials, error_bound }) => assert!(trials >= 1 && error_bound <= 1e-9),
+        verdict => panic!("unexpected verdict {:?}", verdict),
+    }
+    // but with no tolerance, the noise itself is a nonzero polynomial
+    assert!(matches!(noise.test_zero_with_tolerance_with_rng(0.0, 1e-9, &mut rng), Some(FloatVerdict::NonZero { .. })));
+
+    let x_plus_one = VecPoly::new(vec![1.0, 1.0]);
+    match x_plus_one.test_zero_with_tolerance_with_rng(1e-12, 1e-9, &mut rng) {
+        Some(FloatVerdict::NonZero { witness, enclosure }) => assert!(enclosure.contains(witness[0] + 1.0)),
+        verdict => panic!("unexpected verdict {:?}", verdict),
+    }
+
+    // 1e-300 x + 1 rounds to 1, so its enclosure straddles the edge of the tolerance band
+    let edge = VecPoly::new(vec![1e-300, 1.0]);
+    assert!(matches!(edge.test_zero_with_tolerance_with_rng(1.0, 1e-9, &mut rng), Some(FloatVerdict::Inconclusive { .. })));
+    assert_eq!(VecPoly::<f32>::new(vec![0.0]).test_zero_with_tolerance_with_rng(0.0, 1e-9, &mut rng), Some(FloatVerdict::Zero { trials: 1, error_bound: 0.0 }));
+}
@@ -0,0 +1,260 @@
+//! Irreducible polynomials over finite fields, which are the moduli that define extension fields.
+//!
+//! Rabin's test decides irreducibility without factoring: a polynomial f of degree n over GF(q) is
+//! irreducible exactly when it divides x^(q^n) - x, which is the product of the monic irreducible
+//! polynomials whose degree divides n, and shares no factor with x^(q^(n/r)) - x for any prime r
+//! dividing n, which rules out factors of every smaller degree dividing n.
+//!
+//! About one in n monic polynomials of degree n is irreducible, so sampling until the test passes
+//! takes n attempts on average.
+
+use rand::prelude::*;
+use num::BigUint;
+use crate::VecPoly;
+use crate::algebra::Field;
+use crate::field::{FieldInfo, Fp};
+use crate::sample::{RandomFieldElement, SampleSet};
+
+/// Conway polynomials for small fields, as pairs of a prime p and the coefficients of a monic
+/// polynomial of degree n, lowest order term first. Each is irreducible and primitive over GF(p),
+/// so x generates the multiplicative group of GF(p^n), and the lexicographically smallest such
+/// polynomial that is compatible with the Conway polynomials of the subfields of GF(p^n).
+pub const CONWAY_POLYNOMIALS: &[(u64, &[u64])] = &[
+    (2, &[1, 1]),
+    (2, &[1, 1, 1]),
+    (2, &[1, 1, 0, 1]),
+    (2, &[1, 1, 0, 0, 1]),
+    (2, &[1, 0, 1, 0, 0, 1]),
+    (2, &[1, 1, 0, 1, 1, 0, 1]),
+    (2, &[1, 1, 0, 0, 0, 0, 0, 1]),
+    (2, &[1, 0, 1, 1, 1, 0, 0, 0, 1]),
+    (3, &[1, 1]),
+    (3, &[2, 2, 1]),
+    (3, &[1, 2, 0, 1]),
+    (3, &[2, 0, 0, 2, 1]),
+    (3, &[1, 2, 0, 0, 0, 1]),
+    (3, &[2, 2, 1, 0, 2, 0, 1]),
+    (5, &[3, 1]),
+    (5, &[2, 4, 1]),
+    (5, &[3, 3, 0, 1]),
+    (5, &[2, 4, 4, 0, 1]),
+    (7, &[4, 1]),
+    (7, &[3, 6, 1]),
+    (7, &[4, 0, 6, 1]),
+    (7, &[3, 4, 5, 0, 1]),
+];
+
+/// Returns the Conway polynomial of the given degree over GF(P) from `CONWAY_POLYNOMIALS`, or
+/// None if it is not in the table.
+pub fn conway_polynomial<const P: u64>(degree: usize) -> Option<VecPoly<Fp<P>>> {
+    return CONWAY_POLYNOMIALS.iter()
+        .find(|(p, coefficients)| *p == P && coefficients.len() == degree + 1)
+        .map(|(_, coefficients)| VecPoly::from_lowest_first(coefficients.iter().map(|&c| Fp::new(c)).collect()))
+}
+
+/// Returns the distinct prime divisors of n in increasing order.
+fn prime_divisors(mut n: usize) -> Vec<usize> {
+    let mut divisors = vec![];
+    let mut d = 2;
+    while d * d <= n {
+        if n.is_multiple_of(d) {
+            divisors.push(d);
+            while n.is_multiple_of(d) {
+                n /= d;
+            }
+        }
+        d += 1;
+    }
+    if n > 1 {
+        divisors.push(n);
+    }
+    return divisors
+}
+
+/// Returns the squarefree divisors d of n with their Möbius function mu(d), the only divisors
+/// where mu(d) is nonzero.
+fn squarefree_divisors(n: usize) -> Vec<(usize, bool)> {
+    let primes = prime_divisors(n);
+    return (0..1usize << primes.len()).map(|mask| {
+        let d = primes.iter().enumerate().filter(|(i, _)| mask >> i & 1 == 1).map(|(_, &r)| r).product();
+        (d, mask.count_ones() % 2 == 0)
+    }).collect()
+}
+
+impl<T> VecPoly<T> where
+    T: Field + PartialEq + FieldInfo + RandomFieldElement {
+    /// Returns whether the polynomial is irreducible with Rabin's test, using O(n log q) products
+    /// of polynomials of degree less than n. Constants, including zero, are not irreducible.
+    pub fn is_irreducible(&self) -> bool {
+        let n = match self.degree() {
+            Some(n) if n > 0 => n,
+            _ => return false,
+        };
+        let (p, k) = Self::field_size();
+        let q = num::pow(BigUint::from(p), k as usize);
+        let monic = self.monic();
+        let x = &Self::x() % &monic;
+        let proper_degrees: Vec<usize> = prime_divisors(n).into_iter().map(|r| n / r).collect();
+        // x^(q^i) mod f for i = 1, ..., n
+        let mut h = x.clone();
+        for i in 1..=n {
+            h = h.pow_mod(&q, &monic);
+            if proper_degrees.contains(&i) && monic.gcd(&(&h - &x)).degree() != Some(0) {
+                return false
+            }
+        }
+        return h == x
+    }
+
+    /// Returns the number of monic irreducible polynomials of the given degree, which is
+    /// (1/n) sum mu(d) q^(n/d) over the divisors d of n by Gauss's formula.
+    pub fn irreducible_count(degree: usize) -> BigUint {
+        assert!(degree > 0, "irreducible polynomials have positive degree");
+        let (p, k) = Self::field_size();
+        let q = num::pow(BigUint::from(p), k as usize);
+        let mut positive = BigUint::from(0u32);
+        let mut negative = BigUint::from(0u32);
+        for (d, even) in squarefree_divisors(degree) {
+            let term = num::pow(q.clone(), degree / d);
+            if even {
+                positive += term;
+            } else {
+                negative += term;
+            }
+        }
+        return (positive - negative) / BigUint::from(degree)
+    }
+
+    /// Returns the expected number of random monic polynomials of the given degree that
+    /// `random_irreducible_with_rng` draws, q^n divided by the number of irreducible ones. This
+    /// is close to n, and never more than 2n.
+    pub fn expected_irreducible_attempts(degree: usize) -> f64 {
+        assert!(degree > 0, "irreducible polynomials have positive degree");
+        let q = T::cardinality().to_f64();
+        // dividing every term of Gauss's formula by q^n avoids overflow
+        let fraction: f64 = squarefree_divisors(degree).into_iter().map(|(d, even)| {
+            let term = q.powi((degree / d) as i32 - degree as i32);
+            if even { term } else { -term }
+        }).sum();
+        return degree as f64 / fraction
+    }
+
+    /// Returns a uniformly random monic irreducible polynomial of the given degree and the
+    /// number of candidates that were drawn from rng to find it, which averages
+    /// `expected_irreducible_attempts`.
+    pub fn random_irreducible_with_rng<R: Rng + ?Sized>(degree: usize, rng: &mut R) -> (Self, usize) {
+        assert!(degree > 0, "irreducible polynomials have positive degree");
+        let sample_set = T::sample_set();
+        let mut attempts = 0;
+        loop {
+            attempts += 1;
+            let mut coefficients: Vec<T> = (0..degree).map(|_| sample_set.sample(rng)).collect();
+            coefficients.push(T::one());
+            let candidate = VecPoly::from_lowest_first(coefficients);
+            if candidate.is_irreducible() {
+                return (candidate, attempts)
+            }
+        }
+    }
+
+    /// Runs `random_irreducible_with_rng` with thread_rng and returns only the polynomial.
+    pub fn random_irreducible(degree: usize) -> Self {
+        return Self::random_irreducible_with_rng(degree, &mut rand::thread_rng()).0
+    }
+}
+
+/// Returns every monic polynomial of the given degree over GF(P).
+#[cfg(test)]
+fn all_monic<const P: u64>(degree: usize) -> Vec<VecPoly<Fp<P>>> {
+    return (0..P.pow(degree as u32)).map(|mut index| {
+        let mut coefficients = vec![];
+        for _ in 0..degree {
+            coefficients.push(Fp::new(index % P));
+            index /= P;
+        }
+        coefficients.push(Fp::new(1));
+        VecPoly::from_lowest_first(coefficients)
+    }).collect()
+}
+
+/// Checks that the Conway polynomial of the given degree is irreducible and primitive, and that
+/// the norm of x down to each subfield is a root of the Conway polynomial of that subfield.
+#[cfg(test)]
+fn check_conway<const P: u64>(degree: usize) {
+    let modulus = conway_polynomial::<P>(degree).unwrap();
+    assert!(modulus.is_irreducible(), "{:?} is reducible", modulus);
+    let x = VecPoly::<Fp<P>>::x();
+    let one = VecPoly::from_lowest_first(vec![Fp::new(1)]);
+    let order = P.pow(degree as u32) - 1;
+    for r in prime_divisors(order as usize) {
+        assert_ne!(x.pow_mod(&BigUint::from(order / r as u64), &modulus), one, "{:?} is not primitive", modulus);
+    }
+    for m in (1..degree).filter(|m| degree.is_multiple_of(*m)) {
+        let norm = x.pow_mod(&BigUint::from(order / (P.pow(m as u32) - 1)), &modulus);
+        let value = conway_polynomial::<P>(m).unwrap().coefficients().iter().rev()
+            .fold(VecPoly::from_lowest_first(vec![]), |acc, c| &(&(&acc * &norm) + &VecPoly::from_lowest_first(vec![*c])) % &modulus);
+        assert_eq!(value.degree(), None, "{:?} is not compatible with degree {}", modulus, m);
+    }
+}
+
+#[test]
+fn rabin_test_counts_every_irreducible_polynomial() {
+    let mut rng = StdRng::seed_from_u64(34);
+    for degree in 1..=8 {
+        let irreducible: Vec<_> = all_monic::<2>(degree).into_iter().filter(|f| f.is_irreducible()).collect();
+        assert_eq!(BigUint::from(irreducible.len()), VecPoly::<Fp<2>>::irreducible_count(degree));
+    }
+    for degree in 1..=5 {
+        for f in all_monic::<3>(degree) {
+            let factors = f.factor_with_rng(&mut rng).factors;
+            assert_eq!(f.is_irreducible(), factors == vec![(f.clone(), 1)], "{:?}", f);
+        }
+    }
+    assert_eq!(VecPoly::<Fp<2>>::irreducible_count(8), BigUint::from(30u32));
+    assert!(!VecPoly::new(vec![Fp::<5>::new(3)]).is_irreducible());
+    assert!(!VecPoly::<Fp<5>>::new(vec![]).is_irreducible());
+    // 3x + 1 is irreducible even though it is not monic
+    assert!(VecPoly::new(vec![Fp::<5>::new(3), Fp::new(1)]).is_irreducible());
+}
+
+#[test]
+fn conway_polynomials_are_primitive_and_compatible() {
+    for degree in 1..=8 {
+        check_conway::<2>(degree);
+    }
+    for degree in 1..=6 {
+        check_conway::<3>(degree);
+    }
+    for degree in 1..=4 {
+        check_conway::<5>(degree);
+        check_conway::<7>(degree);
+    }
+    assert_eq!(conway_polynomial::<7>(5), None);
+    // GF256 uses the AES modulus x^8 + x^4 + x^3 + x + 1, which is irreducible but not primitive
+    let aes = VecPoly::from_lowest_first([1, 1, 0, 1, 1, 0, 0, 0, 1].iter().map(|&c| Fp::<2>::new(c)).collect());
+    assert!(aes.is_irreducible());
+    assert_ne!(Some(aes), conway_polynomial::<2>(8));
+}
+
+#[test]
+fn random_irreducible_polynomials_take_the_expected_attempts() {
+    use crate::field::GF256;
+
+    let mut rng = StdRng::seed_from_u64(35);
+    let expected = VecPoly::<Fp<3>>::expected_irreducible_attempts(4);
+    // 18 of the 81 monic quartics over GF(3) are irreducible
+    assert!((expected - 4.5).abs() < 1e-9);
+    let samples = 400;
+    let total: usize = (0..samples).map(|_| VecPoly::<Fp<3>>::random_irreducible_with_rng(4, &mut rng).1).sum();
+    assert!((total as f64 / samples as f64 - expected).abs() < 1.0);
+
+    for degree in [1, 2, 5, 12].iter() {
+        let (f, _) = VecPoly::<Fp<65537>>::random_irreducible_with_rng(*degree, &mut rng);
+        assert_eq!(f.degree(), Some(*degree));
+        assert_eq!(f.factor_with_rng(&mut rng).factors, vec![(f.clone(), 1)]);
+        let (g, _) = VecPoly::<GF256>::random_irreducible_with_rng(*degree, &mut rng);
+        assert_eq!(g.distinct_degree_factorization(), vec![(g.clone(), *degree)]);
+        let attempts = VecPoly::<GF256>::expected_irreducible_attempts(*degree);
+        assert!(attempts >= *degree as f64 * 0.99 && attempts <= 2.0 * *degree as f64);
+    }
+}
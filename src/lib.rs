@@ -1,16 +1,36 @@
+#![allow(clippy::needless_return)]
+#![allow(clippy::bool_assert_comparison)]
+
 use rand::prelude::*;
-use rand::distributions::Standard;
-use rand::distributions::uniform::{SampleUniform};
-use num::{Zero, Bounded};
-use std::ops::{Sub, Add, Mul, Div};
-use alga::general::{Multiplicative, AbstractField};
-
-// things that I will never get to because this is a one-day learning project:
-// TODO: we only really need an integral domain for Schwartz-Zippel lemma, we can relax some types
-// TODO: find out if there is any way to remove some traits in definitions of PartialEq and Zero
-// TODO: support multivariate polynomials.
-// TODO: test lots of fields (maybe a finite field).
-// TODO: add different polynomial encodings.
+use num::Zero;
+use std::ops::{Add, Mul};
+use algebra::IntegralDomain;
+use field::{Cardinality, FieldInfo};
+use sample::{Constants, SampleSet, RandomFieldElement};
+
+pub mod agrawal_biswas;
+pub mod algebra;
+pub mod circuit;
+mod division;
+pub mod factor;
+pub mod field;
+pub mod interval;
+pub mod integer;
+pub mod interpolation;
+pub mod irreducible;
+pub mod multiply;
+pub mod multivariate;
+pub mod newton;
+pub mod pit;
+pub mod point_value;
+pub mod primality;
+pub mod rational;
+pub mod roots;
+pub mod sample;
+pub mod sparse;
+mod ops;
+
+pub use pit::{IdentityTest, ProbablyEq, Verdict};
 
 /// Polynomial represents a polynomial with elements of type T.
 pub trait Polynomial<T> {
@@ -20,35 +40,113 @@ pub trait Polynomial<T> {
     /// Returns the result of evaluating the polynomial at a certain element. When the polynomial
     /// has no terms, it returns None.
     fn evaluate(&self, element: T) -> Option<T>;
+
+    /// Returns the degree of the polynomial, which is one less than the order. The zero
+    /// polynomial has no degree, so it returns None.
+    fn degree(&self) -> Option<usize> {
+        return self.order().checked_sub(1)
+    }
 }
 
-/// VecPoly is a struct that represents a polynomial with float coefficients.
+/// MultivariatePolynomial represents a polynomial in one or more variables with elements of type
+/// T. This is what the identity tests in `pit` work with.
+pub trait MultivariatePolynomial<T> {
+    /// Returns the number of variables, so points passed to evaluate_at need at least this many
+    /// elements.
+    fn variables(&self) -> usize;
+
+    /// Returns an upper bound on the total degree of the polynomial.
+    fn total_degree(&self) -> usize;
+
+    /// Returns the result of evaluating the polynomial at a point, with one element per variable.
+    /// When the polynomial has no terms, it returns None.
+    fn evaluate_at(&self, point: &[T]) -> Option<T>;
+}
+
+/// VecPoly is a struct that represents a polynomial with coefficients of type T.
+///
+/// The coefficients are stored in a Vec<T> lowest order term first, so the coefficient of x^i is
+/// at index i. The representation is canonical: the last coefficient is never zero, so the zero
+/// polynomial has no coefficients and the size of the vec minus one is the degree. This means
+/// that the following polynomial:
+///     x^2 + 2
+/// is represented by vec![2,0,1].
 ///
-/// The coefficients are stored in a Vec<f64> and the size of the vec minus one is the order.
-/// This means that the following polynomial:
-///     x^2 + 1
-/// is represented by vec![1,0,1].
-#[derive(Debug, Clone)]
-struct VecPoly<T> {
+/// Because the representation is canonical, `==` and `Hash` compare coefficients directly, which
+/// is exact and deterministic. The randomized identity test is available through `IdentityTest`
+/// and `ProbablyEq`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct VecPoly<T> {
     coefficients: Vec<T>,
 }
 
+impl<T: Zero> VecPoly<T> {
+    /// Creates a polynomial from its coefficients, highest order term first, so x^2 + 2 is
+    /// VecPoly::new(vec![1, 0, 2]). Leading zeros are removed.
+    pub fn new(mut coefficients: Vec<T>) -> Self {
+        coefficients.reverse();
+        return Self::from_lowest_first(coefficients)
+    }
+
+    /// Creates a polynomial from its coefficients, lowest order term first, so x^2 + 2 is
+    /// VecPoly::from_lowest_first(vec![2, 0, 1]). Leading zeros are removed.
+    pub fn from_lowest_first(coefficients: Vec<T>) -> Self {
+        let mut poly = Self { coefficients };
+        poly.normalize();
+        return poly
+    }
+
+    /// Removes leading zero coefficients, restoring the canonical representation.
+    pub(crate) fn normalize(&mut self) {
+        while self.coefficients.last().is_some_and(|c| c.is_zero()) {
+            self.coefficients.pop();
+        }
+    }
+
+    /// Maps the polynomial into a larger ring, for example from GF(p) into an extension GF(p^k),
+    /// by converting each coefficient.
+    ///
+    /// Identity testing a lifted polynomial is much more reliable when the original field is
+    /// small, since the Schwartz-Zippel error d/N shrinks with the size of the field.
+    pub fn lift<U: From<T> + Zero>(&self) -> VecPoly<U> where
+        T: Clone {
+        return VecPoly::from_lowest_first(self.coefficients.iter().map(|c| U::from(c.clone())).collect())
+    }
+}
+
+impl<T> VecPoly<T> {
+    /// Returns the coefficients of the polynomial, lowest order term first.
+    pub fn coefficients(&self) -> &[T] {
+        return &self.coefficients
+    }
+
+    /// Returns the degree of the polynomial, or None for the zero polynomial.
+    pub fn degree(&self) -> Option<usize> {
+        return self.coefficients.len().checked_sub(1)
+    }
+
+    /// Returns the coefficient of the highest order term, or None for the zero polynomial.
+    pub fn leading_coefficient(&self) -> Option<&T> {
+        return self.coefficients.last()
+    }
+}
+
 impl<T> Polynomial<T> for VecPoly<T> where
-    T: Add<Output=T> + Mul<Output=T> + Copy {
+    T: Add<Output=T> + Mul<Output=T> + Clone {
 
     /// The VecPoly implementation for Polynomial sets the order as the length of the coefficient
-    /// vector.
+    /// vector, which is the degree plus one since leading zeros are never stored.
     fn order(&self) -> usize {
         return self.coefficients.len();
     }
 
     /// Returns the evaluation of the polynomial at element, using Horner's method.
     fn evaluate(&self, element: T) -> Option<T> {
-        let coefs = self.coefficients.iter();
-        if let Some(result) = self.coefficients.first() {
-            let mut accumulated = *result;
-            for coef in coefs.skip(1) {
-                accumulated = accumulated * element + *coef;
+        let mut coefs = self.coefficients.iter().rev();
+        if let Some(result) = coefs.next() {
+            let mut accumulated = result.clone();
+            for coef in coefs {
+                accumulated = accumulated * element.clone() + coef.clone();
             }
             return Some(accumulated);
         }
@@ -56,62 +154,83 @@ impl<T> Polynomial<T> for VecPoly<T> where
     }
 }
 
-/// This implements PartialEq for VecPoly<T>, using the polynomial identity test to determine
-/// equality of polynomials.
-impl<T: Add<Output=T> + Mul<Output=T> + Div<Output=T> + Sub<Output=T> + Zero + Copy + Bounded + AbstractField + SampleUniform> PartialEq for VecPoly<T> where
-    Standard: Distribution<T> {
-    fn eq(&self, other: &Self) -> bool {
-        // f(x) = g(x) iff f(x) - g(x) = 0
-        let self_clone = self.clone();
-        let other_clone = other.clone();
-        let sub = self_clone - other_clone;
-        return sub.is_zero();
+/// VecPoly is a MultivariatePolynomial in a single variable, so it can share the identity tests
+/// in `pit`.
+impl<T> MultivariatePolynomial<T> for VecPoly<T> where
+    T: Add<Output=T> + Mul<Output=T> + Clone {
+    fn variables(&self) -> usize {
+        return 1
+    }
+
+    fn total_degree(&self) -> usize {
+        return self.order().saturating_sub(1)
+    }
+
+    fn evaluate_at(&self, point: &[T]) -> Option<T> {
+        return self.evaluate(point[0].clone())
     }
 }
 
-impl<T: Add<Output=T> + Mul<Output=T> + Div<Output=T> + Sub<Output=T> + Zero + Copy + Bounded + AbstractField + SampleUniform> Eq for VecPoly<T> where
-    Standard: Distribution<T> {}
+/// The error probability that `probably_zero` and `probably_eq` aim for.
+pub const DEFAULT_ERROR: f64 = 1e-9;
 
-impl<T: Add<Output=T> + Copy> Add for VecPoly<T> {
-    type Output=Self;
+impl<T: Add<Output=T> + Mul<Output=T> + Zero + Clone> VecPoly<T> {
+    /// Tests whether the polynomial is zero, drawing the random points uniformly from sample_set.
+    /// Returns None if the degree is not smaller than the size of the set, since then the test
+    /// bounds nothing.
+    pub fn test_zero_on_with_rng<S: SampleSet<T> + ?Sized, R: Rng + ?Sized>(&self, sample_set: &S, error: f64, rng: &mut R) -> Option<Verdict<T>> {
+        return pit::test_zero(self, sample_set, error, rng)
+    }
 
-    fn add(self, other: Self) -> Self {
-        return Self {
-            coefficients: self.coefficients.iter().zip(other.coefficients.iter()).map(|(a,b)| *a+*b).collect()
-        }
+    /// Lifts the polynomial into the field U and tests it there. This is how polynomials whose
+    /// degree is too large for their own field are tested, like x^p - x over GF(p), which
+    /// vanishes on GF(p) but only has p roots in GF(p^k).
+    pub fn test_zero_lifted_with_rng<U, R>(&self, error: f64, rng: &mut R) -> Option<Verdict<U>> where
+        U: From<T> + IntegralDomain + FieldInfo + RandomFieldElement,
+        R: Rng + ?Sized {
+        return self.lift::<U>().try_test_zero_with_rng(error, rng)
     }
 }
 
-impl<T: Sub<Output=T> + Copy> Sub for VecPoly<T> {
-    type Output=Self;
+/// The test uses the size of the field: see `pit::test_zero_over_field`. It refuses to run when
+/// the degree is at least the size of the field, and evaluates every element of small fields.
+///
+/// Only an integral domain is needed, so integer and polynomial coefficients can be tested too.
+impl<T: IntegralDomain + FieldInfo + RandomFieldElement> IdentityTest<T> for VecPoly<T> {
+    fn try_test_zero_with_rng<R: Rng + ?Sized>(&self, error: f64, rng: &mut R) -> Option<Verdict<T>> {
+        pit::assert_in_field(self.coefficients.iter());
+        return pit::test_zero_over_field(self, error, rng)
+    }
+}
 
-    fn sub(self, other: Self) -> Self {
-        return Self {
-            coefficients: self.coefficients.iter().zip(other.coefficients.iter()).map(|(a,b)| *a-*b).collect()
-        }
+/// Two polynomials are probably equal when their difference passes the identity test, since
+/// f(x) = g(x) iff f(x) - g(x) = 0.
+impl<T: IntegralDomain + FieldInfo + RandomFieldElement> ProbablyEq for VecPoly<T> {
+    fn probably_eq_with_rng<R: Rng + ?Sized>(&self, other: &Self, error: f64, rng: &mut R) -> bool {
+        return (self - other).test_zero_with_rng(error, rng).is_probably_zero()
     }
 }
 
-impl<T: Add<Output=T> + Mul<Output=T> + Div<Output=T> + Zero + Copy + Bounded + AbstractField + SampleUniform> Zero for VecPoly<T> {
-    /// Returns whether or not the polynomial is zero. According to the Schwartz-Zippel lemma, for
-    /// a nonzero polynomial with degree d over a field of cardinality N, the probability that the
-    /// polynomial is zero if = d/N.
-    ///
-    /// For floats, N is very large so we can be fairly confident by running it 1 time.
+impl<T: IntegralDomain + FieldInfo + RandomFieldElement> VecPoly<T> {
+    /// Runs the identity test with DEFAULT_ERROR, drawing the random points from rng. Passing a
+    /// seeded rng makes the result reproducible, so a failing test can be replayed.
+    pub fn is_zero_with_rng<R: Rng + ?Sized>(&self, rng: &mut R) -> bool {
+        return self.test_zero_with_rng(DEFAULT_ERROR, rng).is_probably_zero()
+    }
+
+    /// Runs the identity test that `==` used before it became structural, drawing the random
+    /// points from rng.
+    #[deprecated(note = "use ProbablyEq::probably_eq_with_rng, or == for exact equality")]
+    pub fn eq_with_rng<R: Rng + ?Sized>(&self, other: &Self, rng: &mut R) -> bool {
+        return self.probably_eq_with_rng(other, DEFAULT_ERROR, rng)
+    }
+}
+
+/// A polynomial is zero when all of its coefficients are, which for the canonical representation
+/// means it has none, so this check is exact. Use `IdentityTest` for the randomized test.
+impl<T: Add<Output=T> + Zero + Clone> Zero for VecPoly<T> {
     fn is_zero(&self) -> bool {
-        let mut rng = rand::thread_rng();
-        // we do the following because the min and max values are large - so assuming the field is
-        // not trivial, these values can be used to bound the rng in a generic way. It will not
-        // always work but most of the time it should. It would be great if we could sample
-        // the entire space uniformly at random in a generic way such that no overflows occur when
-        // evaluating the polnomial.
-        let min_of_range = T::min_value() / (T::id(Multiplicative) + T::id(Multiplicative));
-        let max_of_range = T::max_value() / (T::id(Multiplicative) + T::id(Multiplicative));
-        let rand_point = rng.gen_range(min_of_range, max_of_range);
-        if let Some(eval_result) = self.evaluate(rand_point) {
-            return eval_result.is_zero();
-        }
-        return true
+        return self.coefficients.iter().all(|c| c.is_zero())
     }
 
     fn zero() -> Self {
@@ -119,6 +238,31 @@ impl<T: Add<Output=T> + Mul<Output=T> + Div<Output=T> + Zero + Copy + Bounded +
     }
 }
 
+/// A polynomial ring over an integral domain is again an integral domain, since the leading
+/// coefficient of a product is the product of the leading coefficients.
+impl<T: IntegralDomain + FieldInfo + RandomFieldElement> IntegralDomain for VecPoly<T> {}
+
+/// A polynomial ring is infinite, with the characteristic of its coefficients.
+impl<T: FieldInfo> FieldInfo for VecPoly<T> {
+    fn characteristic() -> u64 {
+        return T::characteristic()
+    }
+
+    fn cardinality() -> Cardinality {
+        return Cardinality::Infinite
+    }
+}
+
+/// Identity tests of polynomials with polynomial coefficients draw constant polynomials from the
+/// sample set of T. Any finite subset of an integral domain works for the Schwartz-Zippel lemma.
+impl<T: RandomFieldElement + Zero> RandomFieldElement for VecPoly<T> {
+    type SampleSet = Constants<T::SampleSet>;
+
+    fn sample_set() -> Constants<T::SampleSet> {
+        return Constants::new(T::sample_set())
+    }
+}
+
 #[test]
 fn check_nonzero() {
     let nonzero_poly = VecPoly::<f64>{
@@ -134,3 +278,193 @@ fn check_zero() {
     };
     assert_eq!(nonzero_poly.is_zero(), true)
 }
+
+#[test]
+fn is_zero_is_exact_in_small_fields() {
+    use crate::field::Fp;
+
+    // x^2 + x vanishes on all of GF(2), but is not the zero polynomial
+    let poly = VecPoly::new(vec![Fp::<2>::new(1), Fp::new(1), Fp::new(0)]);
+    assert_eq!(poly.is_zero(), false);
+    // the randomized test can't bound its error here, so it refuses to run instead
+    assert_eq!(poly.try_test_zero_with_rng(DEFAULT_ERROR, &mut StdRng::seed_from_u64(42)), None);
+    assert_eq!((&poly - &poly).is_zero(), true);
+}
+
+#[test]
+fn seeded_identity_tests_are_reproducible() {
+    use crate::field::Fp;
+
+    // x^2 - 1 is nonzero but has the roots 1 and -1, so single trials can be fooled.
+    let poly = VecPoly::new(vec![Fp::<5>::new(1), Fp::new(0), Fp::from_i64(-1)]);
+    let verdicts = |seed: u64| {
+        let mut rng = StdRng::seed_from_u64(seed);
+        return (0..32).map(|_| poly.is_zero_with_rng(&mut rng)).collect::<Vec<bool>>()
+    };
+    assert_eq!(verdicts(8), verdicts(8));
+    let other = VecPoly::new(vec![Fp::<5>::new(1), Fp::new(0), Fp::new(1)]);
+    let mut first = StdRng::seed_from_u64(9);
+    let mut second = StdRng::seed_from_u64(9);
+    for _ in 0..32 {
+        assert_eq!(poly.probably_eq_with_rng(&other, 0.5, &mut first), poly.probably_eq_with_rng(&other, 0.5, &mut second));
+    }
+}
+
+#[test]
+fn deterministic_rng_replays_a_false_zero() {
+    use crate::field::Fp;
+    use rand::rngs::mock::StepRng;
+
+    // a source that always returns zero always samples the point 0, which is a root of x
+    let x = VecPoly::new(vec![Fp::<101>::new(1), Fp::new(0)]);
+    assert_eq!(x.is_zero_with_rng(&mut StepRng::new(0, 0)), true);
+    let x_plus_one = VecPoly::new(vec![Fp::<101>::new(1), Fp::new(1)]);
+    assert_eq!(x_plus_one.is_zero_with_rng(&mut StepRng::new(0, 0)), false);
+    assert_eq!(x.probably_eq_with_rng(&x_plus_one, DEFAULT_ERROR, &mut StepRng::new(0, 0)), false);
+}
+
+#[test]
+fn identity_test_draws_from_the_given_rng() {
+    use crate::field::Fp;
+
+    struct CountingRng<R> {
+        inner: R,
+        calls: usize,
+    }
+
+    impl<R: RngCore> RngCore for CountingRng<R> {
+        fn next_u32(&mut self) -> u32 {
+            self.calls += 1;
+            return self.inner.next_u32()
+        }
+
+        fn next_u64(&mut self) -> u64 {
+            self.calls += 1;
+            return self.inner.next_u64()
+        }
+
+        fn fill_bytes(&mut self, dest: &mut [u8]) {
+            self.calls += 1;
+            self.inner.fill_bytes(dest)
+        }
+
+        fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+            self.calls += 1;
+            return self.inner.try_fill_bytes(dest)
+        }
+    }
+
+    let mut rng = CountingRng { inner: StdRng::seed_from_u64(1), calls: 0 };
+    let poly = VecPoly::new(vec![Fp::<65537>::new(3), Fp::new(1)]);
+    poly.is_zero_with_rng(&mut rng);
+    assert!(rng.calls > 0);
+}
+
+#[test]
+fn verdict_witness_rechecks_and_bound_meets_target() {
+    use crate::field::Fp;
+
+    let mut rng = StdRng::seed_from_u64(2);
+    // (x - 1)(x - 2) is nonzero at all but two points of GF(65537)
+    let poly = VecPoly::new(vec![Fp::<65537>::new(1), Fp::from_i64(-3), Fp::new(2)]);
+    match poly.test_zero_with_rng(1e-12, &mut rng) {
+        Verdict::NonZero { witness, value } => assert_eq!(poly.evaluate(witness[0]), Some(value)),
+        verdict => panic!("unexpected verdict {:?}", verdict),
+    }
+    let zero = &poly - &poly;
+    assert_eq!(zero.test_zero_with_rng(1e-12, &mut rng), Verdict::ProbablyZero { trials: 1, error_bound: 0.0 });
+
+    // x(x - 1) vanishes on all of {0, 1}, a set too small for degree 2, so the test refuses
+    let fooled = VecPoly::new(vec![Fp::<5>::new(1), Fp::from_i64(-1), Fp::new(0)]);
+    let tiny = [Fp::new(0), Fp::new(1)];
+    assert_eq!(fooled.test_zero_on_with_rng(&tiny[..], 1e-12, &mut rng), None);
+    // over the whole of GF(5) it is caught
+    assert!(!fooled.test_zero_with_rng(1e-12, &mut rng).is_probably_zero());
+}
+
+#[test]
+fn structural_equality_is_consistent_and_hashable() {
+    use crate::field::Fp;
+    use std::collections::HashSet;
+
+    let x_plus_one = VecPoly::new(vec![Fp::<101>::new(1), Fp::new(1)]);
+    let expanded = VecPoly::new(vec![Fp::<101>::new(1), Fp::new(2), Fp::new(1)]);
+    let square = &x_plus_one * &x_plus_one;
+    assert_eq!(square, expanded);
+    assert_eq!(square, square.clone());
+    assert_ne!(square, x_plus_one);
+    // leading zeros are trimmed, so they don't affect equality or hashing
+    assert_eq!(VecPoly::new(vec![Fp::<101>::new(0), Fp::new(1), Fp::new(1)]), x_plus_one);
+
+    let set: HashSet<VecPoly<Fp<101>>> = vec![square, expanded, x_plus_one.clone(), x_plus_one].into_iter().collect();
+    assert_eq!(set.len(), 2);
+}
+
+#[test]
+fn probably_eq_agrees_with_structural_equality() {
+    use crate::field::Fp;
+
+    let mut rng = StdRng::seed_from_u64(12);
+    let a = VecPoly::new(vec![Fp::<65537>::new(3), Fp::new(0), Fp::new(7)]);
+    let b = VecPoly::new(vec![Fp::<65537>::new(1), Fp::new(4)]);
+    let sum = &a + &b;
+    let reordered = &b + &a;
+    assert!(sum.probably_eq_with_rng(&reordered, 1e-12, &mut rng));
+    assert!(sum.probably_eq(&reordered));
+    assert!(!sum.probably_eq_with_rng(&a, 1e-12, &mut rng));
+    assert!(!a.probably_eq(&b));
+    assert!((&sum - &reordered).probably_zero());
+}
+
+#[test]
+fn big_integer_polynomials_are_identity_tested() {
+    use num::BigInt;
+
+    let mut rng = StdRng::seed_from_u64(13);
+    // 2^100 x^20 - 1 overflows every machine integer at almost every point
+    let big = BigInt::from(1u128 << 100);
+    let mut coefficients = vec![BigInt::zero(); 21];
+    coefficients[0] = -BigInt::from(1);
+    coefficients[20] = big;
+    let poly = VecPoly::from_lowest_first(coefficients);
+    match poly.test_zero_with_rng(1e-9, &mut rng) {
+        Verdict::NonZero { witness, value } => assert_eq!(poly.evaluate(witness[0].clone()), Some(value)),
+        verdict => panic!("unexpected verdict {:?}", verdict),
+    }
+    assert!(VecPoly::<BigInt>::new(vec![BigInt::zero(); 3]).probably_zero());
+}
+
+#[test]
+fn nested_polynomials_are_identity_tested() {
+    use crate::field::Fp;
+
+    let mut rng = StdRng::seed_from_u64(14);
+    // x - y as a polynomial in y whose coefficients are polynomials in x
+    let x = VecPoly::new(vec![Fp::<101>::new(1), Fp::new(0)]);
+    let minus_one = VecPoly::new(vec![Fp::<101>::from_i64(-1)]);
+    let difference = VecPoly::from_lowest_first(vec![x.clone(), minus_one]);
+    match difference.test_zero_with_rng(1e-9, &mut rng) {
+        Verdict::NonZero { witness, value } => {
+            // the witness is a constant c, and the value is the polynomial x - c
+            assert!(witness[0].degree().is_none_or(|d| d == 0));
+            assert_eq!(value, &x - &witness[0]);
+        }
+        verdict => panic!("unexpected verdict {:?}", verdict),
+    }
+    let zero: VecPoly<VecPoly<Fp<101>>> = VecPoly::new(vec![VecPoly::new(vec![]); 2]);
+    assert!(zero.probably_zero());
+}
+
+#[test]
+fn nested_polynomials_are_normalized_exactly() {
+    use std::collections::HashSet;
+    use crate::field::Fp;
+
+    // x^5 - x vanishes on GF(5), so only an exact zero check keeps it as a coefficient
+    let fermat = VecPoly::new(vec![Fp::<5>::new(1), Fp::new(0), Fp::new(0), Fp::new(0), Fp::from_i64(-1), Fp::new(0)]);
+    let nested = VecPoly::new(vec![fermat.clone(), VecPoly::zero()]);
+    assert_eq!(nested.coefficients(), &[VecPoly::zero(), fermat.clone()]);
+    assert_ne!(nested, VecPoly::zero());
+    let set: HashSet<_> = (0..100).map(|_| VecPoly::new(vec![fermat.clone()])).collect();
+    assert_eq!(set.len(), 1);
+}
@@ -0,0 +1,251 @@
+//! Sparse multivariate polynomials.
+
+use rand::prelude::*;
+use num::{Zero, One};
+use std::collections::BTreeMap;
+use std::ops::{Add, Sub, Mul};
+use crate::{IdentityTest, MultivariatePolynomial, Verdict, pit};
+use crate::field::FieldInfo;
+use crate::sample::{RandomFieldElement, SampleSet};
+
+/// Returns base^exp using repeated squaring.
+pub(crate) fn pow<T: Mul<Output=T> + One + Clone>(base: T, mut exp: usize) -> T {
+    let mut result = T::one();
+    let mut square = base;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = result * square.clone();
+        }
+        square = square.clone() * square;
+        exp >>= 1;
+    }
+    return result
+}
+
+/// Removes trailing zero exponents, so every monomial has exactly one representation.
+fn canonical_monomial(mut exponents: Vec<usize>) -> Vec<usize> {
+    while exponents.last() == Some(&0) {
+        exponents.pop();
+    }
+    return exponents
+}
+
+/// MultiPoly is a sparse polynomial in the variables x_0, x_1, ... with coefficients of type T.
+///
+/// Each monomial is stored as its vector of exponents, where entry i is the exponent of x_i,
+/// mapped to its coefficient. Only nonzero coefficients are stored. This means that the
+/// following polynomial:
+///     3 x_0^2 x_2 + 1
+/// is represented by {[2, 0, 1]: 3, []: 1}.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MultiPoly<T> {
+    terms: BTreeMap<Vec<usize>, T>,
+}
+
+impl<T: Zero + Clone> MultiPoly<T> {
+    /// Creates a polynomial from (exponents, coefficient) pairs. Coefficients of repeated
+    /// monomials are added together.
+    pub fn from_terms<I: IntoIterator<Item=(Vec<usize>, T)>>(terms: I) -> Self {
+        let mut poly = Self::zero();
+        for (exponents, coefficient) in terms {
+            poly.add_term(exponents, coefficient);
+        }
+        return poly
+    }
+
+    /// Creates the constant polynomial c.
+    pub fn constant(c: T) -> Self {
+        return Self::from_terms(vec![(vec![], c)])
+    }
+
+    /// Creates the polynomial x_i.
+    pub fn variable(i: usize) -> Self where
+        T: One {
+        let mut exponents = vec![0; i + 1];
+        exponents[i] = 1;
+        return Self::from_terms(vec![(exponents, T::one())])
+    }
+
+    /// Returns an iterator over the (exponents, coefficient) pairs of the nonzero terms.
+    pub fn terms(&self) -> impl Iterator<Item=(&[usize], &T)> {
+        return self.terms.iter().map(|(exponents, coefficient)| (exponents.as_slice(), coefficient))
+    }
+
+    /// Returns the degree of the polynomial in the variable x_i.
+    pub fn degree_in(&self, i: usize) -> usize {
+        return self.terms.keys().map(|exponents| exponents.get(i).copied().unwrap_or(0)).max().unwrap_or(0)
+    }
+
+    /// Runs the Schwartz-Zippel test once, drawing every variable independently and uniformly
+    /// from sample_set. For a nonzero polynomial of total degree d this wrongly returns true with
+    /// probability at most d/|sample_set|.
+    pub fn is_zero_on<S: SampleSet<T> + ?Sized>(&self, sample_set: &S) -> bool where
+        T: Add<Output=T> + Mul<Output=T> + One {
+        return self.is_zero_on_with_rng(sample_set, &mut rand::thread_rng())
+    }
+
+    /// Runs the same test as `is_zero_on`, drawing the random point from rng.
+    pub fn is_zero_on_with_rng<S: SampleSet<T> + ?Sized, R: Rng + ?Sized>(&self, sample_set: &S, rng: &mut R) -> bool where
+        T: Add<Output=T> + Mul<Output=T> + One {
+        return pit::schwartz_zippel(self, 1, || sample_set.sample(rng))
+    }
+
+    fn add_term(&mut self, exponents: Vec<usize>, coefficient: T) {
+        let exponents = canonical_monomial(exponents);
+        let sum = match self.terms.get(&exponents) {
+            Some(existing) => existing.clone() + coefficient,
+            None => coefficient,
+        };
+        if sum.is_zero() {
+            self.terms.remove(&exponents);
+        } else {
+            self.terms.insert(exponents, sum);
+        }
+    }
+}
+
+impl<T> MultivariatePolynomial<T> for MultiPoly<T> where
+    T: Add<Output=T> + Mul<Output=T> + Zero + One + Clone {
+    /// The number of variables is one more than the highest index of a variable that appears.
+    fn variables(&self) -> usize {
+        return self.terms.keys().map(|exponents| exponents.len()).max().unwrap_or(0)
+    }
+
+    /// Returns the largest sum of exponents over all terms.
+    fn total_degree(&self) -> usize {
+        return self.terms.keys().map(|exponents| exponents.iter().sum()).max().unwrap_or(0)
+    }
+
+    /// Returns the evaluation of the polynomial at point, term by term.
+    fn evaluate_at(&self, point: &[T]) -> Option<T> {
+        if self.terms.is_empty() {
+            return None
+        }
+        let mut accumulated = T::zero();
+        for (exponents, coefficient) in self.terms.iter() {
+            let mut term = coefficient.clone();
+            for (x, exp) in point.iter().zip(exponents.iter()) {
+                term = term * pow(x.clone(), *exp);
+            }
+            accumulated = accumulated + term;
+        }
+        return Some(accumulated)
+    }
+}
+
+/// Tests the polynomial over its field of coefficients, see `pit::test_zero_over_field`.
+impl<T> IdentityTest<T> for MultiPoly<T> where
+    T: Add<Output=T> + Mul<Output=T> + Zero + One + Clone + FieldInfo + RandomFieldElement {
+    fn try_test_zero_with_rng<R: Rng + ?Sized>(&self, error: f64, rng: &mut R) -> Option<Verdict<T>> {
+        pit::assert_in_field(self.terms.values());
+        return pit::test_zero_over_field(self, error, rng)
+    }
+}
+
+impl<T: Zero + Clone> Add for MultiPoly<T> {
+    type Output=Self;
+
+    fn add(mut self, other: Self) -> Self {
+        for (exponents, coefficient) in other.terms {
+            self.add_term(exponents, coefficient);
+        }
+        return self
+    }
+}
+
+impl<T: Zero + Sub<Output=T> + Clone> Sub for MultiPoly<T> {
+    type Output=Self;
+
+    fn sub(mut self, other: Self) -> Self {
+        for (exponents, coefficient) in other.terms {
+            self.add_term(exponents, T::zero() - coefficient);
+        }
+        return self
+    }
+}
+
+impl<T: Zero + Mul<Output=T> + Clone> Mul for MultiPoly<T> {
+    type Output=Self;
+
+    fn mul(self, other: Self) -> Self {
+        let mut product = Self::zero();
+        for (left, a) in self.terms.iter() {
+            for (right, b) in other.terms.iter() {
+                let mut exponents = vec![0; left.len().max(right.len())];
+                for (i, exp) in left.iter().enumerate() {
+                    exponents[i] += exp;
+                }
+                for (i, exp) in right.iter().enumerate() {
+                    exponents[i] += exp;
+                }
+                product.add_term(exponents, a.clone() * b.clone());
+            }
+        }
+        return product
+    }
+}
+
+/// Since zero coefficients are never stored, the zero polynomial is exactly the one with no
+/// terms, so this check is exact. Use `is_zero_on` for the randomized test.
+impl<T: Zero + Clone> Zero for MultiPoly<T> {
+    fn zero() -> Self {
+        return Self { terms: BTreeMap::new() }
+    }
+
+    fn is_zero(&self) -> bool {
+        return self.terms.is_empty()
+    }
+}
+
+#[test]
+fn multipoly_degrees_and_evaluation() {
+    // 3 x_0^2 x_2 + x_1 + 1
+    let poly = MultiPoly::from_terms(vec![(vec![2, 0, 1], 3.0), (vec![0, 1], 1.0), (vec![], 1.0)]);
+    assert_eq!(poly.variables(), 3);
+    assert_eq!(poly.total_degree(), 3);
+    assert_eq!(poly.degree_in(0), 2);
+    assert_eq!(poly.degree_in(1), 1);
+    assert_eq!(poly.degree_in(5), 0);
+    assert_eq!(poly.evaluate_at(&[2.0, 5.0, -1.0]), Some(-6.0));
+    assert_eq!(MultiPoly::<f64>::zero().evaluate_at(&[]), None);
+}
+
+#[test]
+fn multipoly_ring_operations() {
+    use crate::field::Fp;
+
+    let x = MultiPoly::<Fp<101>>::variable(0);
+    let y = MultiPoly::<Fp<101>>::variable(1);
+    let two = MultiPoly::constant(Fp::new(2));
+    // (x + y)^2 = x^2 + 2xy + y^2
+    let square = (x.clone() + y.clone()) * (x.clone() + y.clone());
+    let expanded = x.clone() * x.clone() + two * x.clone() * y.clone() + y.clone() * y.clone();
+    assert_eq!(square, expanded);
+    // (x + y)(x - y) - (x^2 - y^2) = 0
+    let difference = (x.clone() + y.clone()) * (x.clone() - y.clone()) - (x.clone() * x - y.clone() * y);
+    assert!(difference.is_zero());
+    assert_eq!(difference.terms().count(), 0);
+}
+
+#[test]
+fn multipoly_schwartz_zippel() {
+    use crate::field::Fp;
+
+    let field: Vec<Fp<103>> = (0..103).map(Fp::new).collect();
+    let x = MultiPoly::<Fp<103>>::variable(0);
+    let y = MultiPoly::<Fp<103>>::variable(1);
+    let one = MultiPoly::constant(Fp::one());
+    // x^2 + 1 has no roots in GF(103), so neither does x^2 y^2 + 1 + (xy - yx)
+    let nonzero = x.clone() * x.clone() * y.clone() * y.clone() + one + (x.clone() * y.clone() - y.clone() * x.clone());
+    assert_eq!(nonzero.is_zero_on(&field), false);
+    let zero = (x.clone() + y.clone()) * (x.clone() + y.clone()) - (x.clone() * x.clone() + x.clone() * y.clone() + y.clone() * x.clone() + y.clone() * y.clone());
+    assert_eq!(zero.is_zero_on(&field), true);
+
+    // x y vanishes whenever either variable is sampled as zero, which a seeded rng replays
+    let product = x * y;
+    let verdicts = |seed: u64| {
+        let mut rng = StdRng::seed_from_u64(seed);
+        return (0..200).map(|_| product.is_zero_on_with_rng(&field, &mut rng)).collect::<Vec<bool>>()
+    };
+    assert_eq!(verdicts(4), verdicts(4));
+}
@@ -0,0 +1,162 @@
+//! Polynomials in the Newton basis of distinct nodes.
+
+use rand::Rng;
+use crate::{IdentityTest, Polynomial, VecPoly, Verdict};
+use crate::algebra::Field;
+
+/// NewtonPoly is a polynomial in the Newton basis of its nodes x_0, ..., x_(n-1):
+///     c_0 + c_1 (x - x_0) + c_2 (x - x_0)(x - x_1) + ...
+///
+/// The coefficients are the divided differences of the interpolated values, so a new point can
+/// be added in O(n) operations without touching the existing coefficients.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NewtonPoly<T> {
+    nodes: Vec<T>,
+    coefficients: Vec<T>,
+}
+
+impl<T: Field + PartialEq> NewtonPoly<T> {
+    /// Creates the zero polynomial with no nodes.
+    pub fn new() -> Self {
+        return Self { nodes: vec![], coefficients: vec![] }
+    }
+
+    /// Returns the nodes x_0, ..., x_(n-1).
+    pub fn nodes(&self) -> &[T] {
+        return &self.nodes
+    }
+
+    /// Returns the coefficients c_0, ..., c_(n-1) in the Newton basis.
+    pub fn coefficients(&self) -> &[T] {
+        return &self.coefficients
+    }
+
+    /// Extends the polynomial to also pass through (x, y), adding x as a node. The new
+    /// coefficient is (y - p(x)) / prod (x - x_i), which keeps the values at the other nodes.
+    /// Panics if x is already a node.
+    pub fn push(&mut self, x: T, y: T) {
+        let mut value = T::zero();
+        let mut basis = T::one();
+        for (node, c) in self.nodes.iter().zip(self.coefficients.iter()) {
+            value = value + c.clone() * basis.clone();
+            basis = basis * (x.clone() - node.clone());
+        }
+        assert!(!basis.is_zero(), "the points must be distinct");
+        self.coefficients.push((y - value) / basis);
+        self.nodes.push(x);
+    }
+
+    /// Converts poly into the Newton basis of the nodes by interpolating its values at them.
+    /// Panics unless there are more nodes than the degree of poly, or if a node is repeated.
+    pub fn from_coefficients(poly: &VecPoly<T>, nodes: Vec<T>) -> Self {
+        assert!(poly.degree().is_none_or(|d| d < nodes.len()), "{} nodes can't represent a polynomial of degree {:?}", nodes.len(), poly.degree());
+        let mut newton = Self::new();
+        for x in nodes.into_iter() {
+            let y = poly.evaluate(x.clone()).unwrap_or_else(T::zero);
+            newton.push(x, y);
+        }
+        return newton
+    }
+
+    /// Expands the polynomial into coefficients in the monomial basis, which takes O(n^2)
+    /// operations.
+    pub fn to_coefficients(&self) -> VecPoly<T> {
+        let mut accumulated = VecPoly::from_lowest_first(vec![]);
+        for (node, c) in self.nodes.iter().zip(self.coefficients.iter()).rev() {
+            let linear = VecPoly::from_lowest_first(vec![T::zero() - node.clone(), T::one()]);
+            accumulated = &(&accumulated * &linear) + &VecPoly::from_lowest_first(vec![c.clone()]);
+        }
+        return accumulated
+    }
+}
+
+impl<T: Field + PartialEq> Default for NewtonPoly<T> {
+    fn default() -> Self {
+        return Self::new()
+    }
+}
+
+impl<T: Field + PartialEq> Polynomial<T> for NewtonPoly<T> {
+    /// Returns the number of nodes after dropping trailing zero coefficients, which is exact
+    /// since the basis polynomials have distinct degrees.
+    fn order(&self) -> usize {
+        return self.coefficients.iter().rposition(|c| !c.is_zero()).map_or(0, |i| i + 1)
+    }
+
+    /// Returns the evaluation of the polynomial at element with the nested form of Horner's
+    /// method, c_0 + (x - x_0)(c_1 + (x - x_1)(c_2 + ...)).
+    fn evaluate(&self, element: T) -> Option<T> {
+        let order = self.order();
+        let mut terms = self.nodes[..order].iter().zip(self.coefficients[..order].iter()).rev();
+        let (_, top) = terms.next()?;
+        let mut accumulated = top.clone();
+        for (node, c) in terms {
+            accumulated = accumulated * (element.clone() - node.clone()) + c.clone();
+        }
+        return Some(accumulated)
+    }
+}
+
+/// The Newton basis is a basis, so the polynomial is zero exactly when every coefficient is. If
+/// c_k is the first nonzero one, the value at x_k is c_k prod_{i<k} (x_k - x_i), which is nonzero
+/// since the nodes are distinct. The test is deterministic and has error bound 0.
+impl<T: Field + PartialEq> IdentityTest<T> for NewtonPoly<T> {
+    fn try_test_zero_with_rng<R: Rng + ?Sized>(&self, _error: f64, _rng: &mut R) -> Option<Verdict<T>> {
+        if let Some(k) = self.coefficients.iter().position(|c| !c.is_zero()) {
+            let witness = self.nodes[k].clone();
+            let value = self.evaluate(witness.clone()).expect("a polynomial with a nonzero coefficient has a value");
+            return Some(Verdict::NonZero { witness: vec![witness], value })
+        }
+        return Some(Verdict::ProbablyZero { trials: self.coefficients.len(), error_bound: 0.0 })
+    }
+}
+
+#[test]
+fn newton_conversions_are_lossless() {
+    use crate::field::Fp;
+    use rand::prelude::*;
+
+    let mut rng = StdRng::seed_from_u64(45);
+    for degree in [0usize, 1, 4, 9].iter() {
+        let coefficients: Vec<Fp<65537>> = (0..=*degree).map(|_| rng.gen()).collect();
+        let poly = VecPoly::from_lowest_first(coefficients);
+        let nodes: Vec<Fp<65537>> = (0..*degree as u64 + 3).map(|i| Fp::new(i * 7 + 1)).collect();
+        let newton = NewtonPoly::from_coefficients(&poly, nodes);
+        assert_eq!(newton.to_coefficients(), poly);
+        assert_eq!(newton.degree(), poly.degree());
+        let x: Fp<65537> = rng.gen();
+        assert_eq!(newton.evaluate(x), poly.evaluate(x));
+    }
+    assert_eq!(NewtonPoly::<Fp<65537>>::new().evaluate(Fp::new(1)), None);
+}
+
+#[test]
+fn newton_identity_tests_are_exact() {
+    use crate::field::Fp;
+    use rand::prelude::*;
+
+    let mut rng = StdRng::seed_from_u64(44);
+    let nodes: Vec<Fp<101>> = (0..6).map(|i| Fp::new(i * 3 + 1)).collect();
+    // (x - 1)(x - 4) vanishes at the first two nodes, so the witness is the third
+    let poly = VecPoly::new(vec![Fp::new(1), Fp::from_i64(-5), Fp::new(4)]);
+    let newton = NewtonPoly::from_coefficients(&poly, nodes.clone());
+    assert_eq!(newton.to_coefficients(), poly);
+    assert_eq!(newton.degree(), Some(2));
+    match newton.test_zero_with_rng(1e-9, &mut rng) {
+        Verdict::NonZero { witness, value } => {
+            assert_eq!(witness, vec![nodes[2]]);
+            assert_eq!(poly.evaluate(witness[0]), Some(value));
+        }
+        verdict => panic!("unexpected verdict {:?}", verdict),
+    }
+    let zero = NewtonPoly::from_coefficients(&VecPoly::from_lowest_first(vec![]), nodes);
+    assert_eq!(zero.test_zero_with_rng(1e-9, &mut rng), Verdict::ProbablyZero { trials: 6, error_bound: 0.0 });
+}
+
+#[test]
+#[should_panic(expected = "the points must be distinct")]
+fn newton_rejects_repeated_nodes() {
+    let mut poly = NewtonPoly::new();
+    poly.push(1.0, 2.0);
+    poly.push(1.0, 3.0);
+}
@@ -0,0 +1,377 @@
+//! Ring arithmetic for VecPoly.
+
+use num::{Zero, One};
+use std::ops::{Add, Sub, Mul, Div, Neg, AddAssign, SubAssign, MulAssign, DivAssign};
+use crate::{VecPoly, multiply};
+
+impl<'a, T: Add<Output=T> + Zero + Clone> Add<&'a VecPoly<T>> for &'a VecPoly<T> {
+    type Output=VecPoly<T>;
+
+    /// Adds two polynomials term by term, aligning coefficients by degree.
+    fn add(self, other: &'a VecPoly<T>) -> VecPoly<T> {
+        let mut coefficients = vec![T::zero(); self.coefficients.len().max(other.coefficients.len())];
+        for (i, a) in self.coefficients.iter().enumerate() {
+            coefficients[i] = a.clone();
+        }
+        for (i, b) in other.coefficients.iter().enumerate() {
+            coefficients[i] = std::mem::replace(&mut coefficients[i], T::zero()) + b.clone();
+        }
+        return VecPoly::from_lowest_first(coefficients)
+    }
+}
+
+impl<T: Add<Output=T> + Zero + Clone> Add for VecPoly<T> {
+    type Output=Self;
+
+    fn add(self, other: Self) -> Self {
+        return &self + &other
+    }
+}
+
+impl<'a, T: Sub<Output=T> + Zero + Clone> Sub<&'a VecPoly<T>> for &'a VecPoly<T> {
+    type Output=VecPoly<T>;
+
+    /// Subtracts two polynomials term by term, aligning coefficients by degree.
+    fn sub(self, other: &'a VecPoly<T>) -> VecPoly<T> {
+        let mut coefficients = vec![T::zero(); self.coefficients.len().max(other.coefficients.len())];
+        for (i, a) in self.coefficients.iter().enumerate() {
+            coefficients[i] = a.clone();
+        }
+        for (i, b) in other.coefficients.iter().enumerate() {
+            coefficients[i] = std::mem::replace(&mut coefficients[i], T::zero()) - b.clone();
+        }
+        return VecPoly::from_lowest_first(coefficients)
+    }
+}
+
+impl<T: Sub<Output=T> + Zero + Clone> Sub for VecPoly<T> {
+    type Output=Self;
+
+    fn sub(self, other: Self) -> Self {
+        return &self - &other
+    }
+}
+
+impl<T: Neg<Output=T> + Clone> Neg for &VecPoly<T> {
+    type Output=VecPoly<T>;
+
+    fn neg(self) -> VecPoly<T> {
+        return VecPoly { coefficients: self.coefficients.iter().map(|a| -a.clone()).collect() }
+    }
+}
+
+impl<T: Neg<Output=T> + Clone> Neg for VecPoly<T> {
+    type Output=Self;
+
+    fn neg(self) -> Self {
+        return -&self
+    }
+}
+
+impl<'a, T: Add<Output=T> + Sub<Output=T> + Mul<Output=T> + Zero + Clone> Mul<&'a VecPoly<T>> for &'a VecPoly<T> {
+    type Output=VecPoly<T>;
+
+    /// Multiplies two polynomials. The coefficient of x^k in the product is the sum of a_i * b_j
+    /// over i + j = k, which `multiply::karatsuba` computes with the schoolbook method for short
+    /// polynomials and Karatsuba's method once both have KARATSUBA_THRESHOLD coefficients.
+    fn mul(self, other: &'a VecPoly<T>) -> VecPoly<T> {
+        // the leading coefficients can still multiply to zero in rings with zero divisors
+        return VecPoly::from_lowest_first(multiply::karatsuba(&self.coefficients, &other.coefficients))
+    }
+}
+
+impl<T: Add<Output=T> + Sub<Output=T> + Mul<Output=T> + Zero + Clone> Mul for VecPoly<T> {
+    type Output=Self;
+
+    fn mul(self, other: Self) -> Self {
+        return &self * &other
+    }
+}
+
+impl<T: Mul<Output=T> + Zero + Clone> Mul<T> for &VecPoly<T> {
+    type Output=VecPoly<T>;
+
+    /// Multiplies every coefficient by a scalar.
+    fn mul(self, scalar: T) -> VecPoly<T> {
+        return VecPoly::from_lowest_first(self.coefficients.iter().map(|a| a.clone() * scalar.clone()).collect())
+    }
+}
+
+impl<T: Mul<Output=T> + Zero + Clone> Mul<T> for VecPoly<T> {
+    type Output=Self;
+
+    fn mul(self, scalar: T) -> Self {
+        return &self * scalar
+    }
+}
+
+impl<T: Div<Output=T> + Zero + Clone> Div<T> for &VecPoly<T> {
+    type Output=VecPoly<T>;
+
+    /// Divides every coefficient by a scalar.
+    fn div(self, scalar: T) -> VecPoly<T> {
+        return VecPoly::from_lowest_first(self.coefficients.iter().map(|a| a.clone() / scalar.clone()).collect())
+    }
+}
+
+impl<T: Div<Output=T> + Zero + Clone> Div<T> for VecPoly<T> {
+    type Output=Self;
+
+    fn div(self, scalar: T) -> Self {
+        return &self / scalar
+    }
+}
+
+impl<T: Add<Output=T> + Zero + Clone> AddAssign<&VecPoly<T>> for VecPoly<T> {
+    fn add_assign(&mut self, other: &Self) {
+        *self = &*self + other;
+    }
+}
+
+impl<T: Add<Output=T> + Zero + Clone> AddAssign for VecPoly<T> {
+    fn add_assign(&mut self, other: Self) {
+        *self += &other;
+    }
+}
+
+impl<T: Sub<Output=T> + Zero + Clone> SubAssign<&VecPoly<T>> for VecPoly<T> {
+    fn sub_assign(&mut self, other: &Self) {
+        *self = &*self - other;
+    }
+}
+
+impl<T: Sub<Output=T> + Zero + Clone> SubAssign for VecPoly<T> {
+    fn sub_assign(&mut self, other: Self) {
+        *self -= &other;
+    }
+}
+
+impl<T: Add<Output=T> + Sub<Output=T> + Mul<Output=T> + Zero + Clone> MulAssign<&VecPoly<T>> for VecPoly<T> {
+    fn mul_assign(&mut self, other: &Self) {
+        *self = &*self * other;
+    }
+}
+
+impl<T: Add<Output=T> + Sub<Output=T> + Mul<Output=T> + Zero + Clone> MulAssign for VecPoly<T> {
+    fn mul_assign(&mut self, other: Self) {
+        *self *= &other;
+    }
+}
+
+impl<T: Mul<Output=T> + Zero + Clone> MulAssign<T> for VecPoly<T> {
+    fn mul_assign(&mut self, scalar: T) {
+        for a in self.coefficients.iter_mut() {
+            *a = std::mem::replace(a, T::zero()) * scalar.clone();
+        }
+        self.normalize();
+    }
+}
+
+impl<T: Div<Output=T> + Zero + Clone> DivAssign<T> for VecPoly<T> {
+    fn div_assign(&mut self, scalar: T) {
+        for a in self.coefficients.iter_mut() {
+            *a = std::mem::replace(a, T::zero()) / scalar.clone();
+        }
+        self.normalize();
+    }
+}
+
+impl<T: Add<Output=T> + Sub<Output=T> + Mul<Output=T> + Zero + One + Clone> One for VecPoly<T> {
+    /// Returns the constant polynomial 1.
+    fn one() -> Self {
+        return VecPoly { coefficients: vec![T::one()] }
+    }
+}
+
+impl<T: Add<Output=T> + Sub<Output=T> + Mul<Output=T> + Zero + One + Clone> VecPoly<T> {
+    /// Raises the polynomial to the power exp using repeated squaring, which takes O(log exp)
+    /// multiplications.
+    pub fn pow(&self, mut exp: u64) -> Self {
+        let mut result = Self::one();
+        let mut square = self.clone();
+        while exp > 0 {
+            if exp & 1 == 1 {
+                result *= &square;
+            }
+            exp >>= 1;
+            if exp > 0 {
+                square = &square * &square;
+            }
+        }
+        return result
+    }
+
+    /// Returns the formal derivative, where x^k becomes k x^(k-1) with k computed as 1 + ... + 1
+    /// in T.
+    pub fn derivative(&self) -> Self {
+        let mut k = T::zero();
+        let mut coefficients = Vec::with_capacity(self.coefficients.len().saturating_sub(1));
+        for c in self.coefficients.iter().skip(1) {
+            k = k + T::one();
+            coefficients.push(c.clone() * k.clone());
+        }
+        return VecPoly::from_lowest_first(coefficients)
+    }
+}
+
+/// Checks the ring axioms on every triple of the given polynomials.
+#[cfg(test)]
+fn check_ring_axioms<T, E>(polys: &[VecPoly<T>], eq: E) where
+    T: Add<Output=T> + Sub<Output=T> + Mul<Output=T> + Neg<Output=T> + Zero + One + Clone + std::fmt::Debug,
+    E: Fn(&VecPoly<T>, &VecPoly<T>) -> bool {
+    let zero = VecPoly::from_lowest_first(vec![]);
+    for a in polys {
+        assert!(eq(&(a + &zero), a));
+        assert!(eq(&(a * &VecPoly::one()), a));
+        assert!(eq(&(a + &-a), &zero));
+        for b in polys {
+            assert!(eq(&(a + b), &(b + a)));
+            assert!(eq(&(a * b), &(b * a)));
+            assert!(eq(&(&(a - b) + b), a));
+            for c in polys {
+                assert!(eq(&(&(a + b) + c), &(a + &(b + c))));
+                assert!(eq(&(&(a * b) * c), &(a * &(b * c))));
+                assert!(eq(&(a * &(b + c)), &(&(a * b) + &(a * c))));
+            }
+        }
+    }
+}
+
+#[test]
+fn ring_axioms_over_floats() {
+    // small integer coefficients keep every float operation exact, so coefficients can be
+    // compared directly
+    let polys = vec![
+        VecPoly::new(vec![1.0, 0.0, 1.0]),
+        VecPoly::new(vec![2.0, -3.0, 0.5]),
+        VecPoly::new(vec![4.0, -1.0]),
+        VecPoly::new(vec![1.0, 0.0, 0.0, 0.0, -2.0]),
+        VecPoly::new(vec![0.0, 0.0, 0.0]),
+    ];
+    check_ring_axioms(&polys, |a: &VecPoly<f64>, b: &VecPoly<f64>| a.coefficients == b.coefficients);
+}
+
+#[test]
+fn ring_axioms_over_prime_field() {
+    use crate::ProbablyEq;
+    use crate::field::Fp;
+    use rand::prelude::*;
+
+    let mut rng = StdRng::seed_from_u64(5);
+    let polys: Vec<VecPoly<Fp<65537>>> = (1..5).map(|len| VecPoly::new((0..len).map(|_| rng.gen()).collect())).collect();
+    check_ring_axioms(&polys, |a: &VecPoly<Fp<65537>>, b: &VecPoly<Fp<65537>>| a.coefficients == b.coefficients);
+    // the identity test agrees with comparing coefficients
+    check_ring_axioms(&polys, |a: &VecPoly<Fp<65537>>, b: &VecPoly<Fp<65537>>| a.probably_eq(b));
+}
+
+#[test]
+fn binomial_square_identity() {
+    use crate::field::Fp;
+
+    // (x + 1)^2 = x^2 + 2x + 1
+    let x_plus_one = VecPoly::new(vec![Fp::<101>::new(1), Fp::new(1)]);
+    let expanded = VecPoly::new(vec![Fp::new(1), Fp::new(2), Fp::new(1)]);
+    assert!(&x_plus_one * &x_plus_one == expanded);
+    assert!(x_plus_one.pow(2) == expanded);
+
+    let float_square = VecPoly::new(vec![1.0, 1.0]).pow(2);
+    assert_eq!(float_square.coefficients(), &[1.0, 2.0, 1.0]);
+}
+
+#[test]
+fn pow_matches_repeated_multiplication() {
+    let x_plus_two = VecPoly::new(vec![1.0, 2.0]);
+    assert_eq!(x_plus_two.pow(0).coefficients(), &[1.0]);
+    assert_eq!(x_plus_two.pow(3).coefficients(), &[8.0, 12.0, 6.0, 1.0]);
+    let mut repeated = VecPoly::one();
+    for _ in 0..7 {
+        repeated *= &x_plus_two;
+    }
+    assert_eq!(repeated.coefficients(), x_plus_two.pow(7).coefficients());
+}
+
+#[test]
+fn derivatives_follow_the_power_rule() {
+    use crate::field::Fp;
+
+    // d/dx (x^3 + 2x^2 + 5) = 3x^2 + 4x
+    assert_eq!(VecPoly::new(vec![1, 2, 0, 5]).derivative().coefficients(), &[0, 4, 3]);
+    assert_eq!(VecPoly::new(vec![7]).derivative().degree(), None);
+    // in characteristic 5, d/dx x^5 = 5x^4 = 0
+    assert_eq!(VecPoly::new(vec![Fp::<5>::new(1), Fp::new(0), Fp::new(0), Fp::new(0), Fp::new(0), Fp::new(1)]).derivative().degree(), None);
+}
+
+#[test]
+fn scalar_and_assign_operations() {
+    // 2x + 4
+    let mut poly = VecPoly::new(vec![2.0, 4.0]);
+    assert_eq!((&poly * 3.0).coefficients(), &[12.0, 6.0]);
+    assert_eq!((&poly / 2.0).coefficients(), &[2.0, 1.0]);
+    assert_eq!((&poly * 0.0).degree(), None);
+    poly *= 0.5;
+    assert_eq!(poly.coefficients(), &[2.0, 1.0]);
+    poly /= 0.5;
+    assert_eq!(poly.coefficients(), &[4.0, 2.0]);
+    poly += VecPoly::new(vec![1.0, 1.0]);
+    assert_eq!(poly.coefficients(), &[5.0, 3.0]);
+    poly -= &VecPoly::new(vec![3.0, 3.0]);
+    assert_eq!(poly.coefficients(), &[2.0]);
+    assert_eq!((-poly).coefficients(), &[-2.0]);
+}
+
+#[test]
+fn add_and_sub_align_by_degree() {
+    // (x^2 + 1) + (3x^5 + 2x) = 3x^5 + x^2 + 2x + 1
+    let small = VecPoly::new(vec![1, 0, 1]);
+    let large = VecPoly::new(vec![3, 0, 0, 0, 2, 0]);
+    assert_eq!((&small + &large).coefficients(), &[1, 2, 1, 0, 0, 3]);
+    assert_eq!((&large + &small).coefficients(), &[1, 2, 1, 0, 0, 3]);
+    assert_eq!((&small - &large).coefficients(), &[1, -2, 1, 0, 0, -3]);
+    assert_eq!((&large - &small).coefficients(), &[-1, 2, -1, 0, 0, 3]);
+}
+
+#[test]
+fn leading_terms_cancel() {
+    // (x^3 + x) - (x^3 - 1) = x + 1, which has degree 1 rather than 3
+    let difference = VecPoly::new(vec![1, 0, 1, 0]) - VecPoly::new(vec![1, 0, 0, -1]);
+    assert_eq!(difference.coefficients(), &[1, 1]);
+    assert_eq!(difference.degree(), Some(1));
+    assert_eq!(difference.leading_coefficient(), Some(&1));
+    let zero = VecPoly::new(vec![2, 5]) - VecPoly::new(vec![2, 5]);
+    assert_eq!(zero.degree(), None);
+    assert_eq!(zero.leading_coefficient(), None);
+}
+
+#[test]
+fn leading_zeros_are_trimmed() {
+    use crate::Polynomial;
+
+    let padded = VecPoly::new(vec![0.0, 0.0, 1.0, 0.0, 1.0]);
+    assert_eq!(padded.coefficients(), &[1.0, 0.0, 1.0]);
+    assert_eq!(padded.order(), 3);
+    assert_eq!(padded.degree(), Some(2));
+    assert_eq!(VecPoly::from_lowest_first(vec![1.0, 0.0, 1.0, 0.0]).coefficients(), &[1.0, 0.0, 1.0]);
+    assert_eq!(VecPoly::new(vec![0.0, 0.0]).order(), 0);
+    assert_eq!(padded.evaluate(2.0), Some(5.0));
+}
+
+#[test]
+fn ring_axioms_over_rationals() {
+    use num::BigRational;
+
+    let q = |n: i64, d: i64| BigRational::new(n.into(), d.into());
+    // tenths have no exact float representation, so associativity fails for f64
+    let (a, b, c) = (VecPoly::new(vec![0.1, 0.7]), VecPoly::new(vec![0.2, 0.1]), VecPoly::new(vec![0.3, 0.2]));
+    assert!(&(&a + &b) + &c != &a + &(&b + &c));
+    let polys = vec![
+        VecPoly::new(vec![q(1, 10), q(7, 10)]),
+        VecPoly::new(vec![q(2, 10), q(1, 10)]),
+        VecPoly::new(vec![q(3, 10), q(2, 10)]),
+        VecPoly::new(vec![q(-1, 3), q(0, 1), q(5, 7)]),
+        VecPoly::new(vec![]),
+    ];
+    check_ring_axioms(&polys, |a: &VecPoly<BigRational>, b: &VecPoly<BigRational>| a == b);
+    // (x + 1/3)^3 expands exactly
+    let cube = VecPoly::new(vec![q(1, 1), q(1, 3)]).pow(3);
+    assert_eq!(cube.coefficients(), &[q(1, 27), q(1, 3), q(1, 1), q(1, 1)]);
+}
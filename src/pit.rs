@@ -0,0 +1,317 @@
+//! Randomized polynomial identity testing shared by every polynomial representation in the crate.
+
+use rand::Rng;
+use num::Zero;
+use crate::{MultivariatePolynomial, DEFAULT_ERROR};
+use crate::field::{Cardinality, FieldInfo};
+use crate::sample::{RandomFieldElement, SampleSet};
+
+/// Returns whether the polynomial evaluates to zero at the point. A polynomial with no terms is
+/// zero everywhere.
+pub fn is_zero_at<T: Zero, P: MultivariatePolynomial<T> + ?Sized>(poly: &P, point: &[T]) -> bool {
+    if let Some(eval_result) = poly.evaluate_at(point) {
+        return eval_result.is_zero();
+    }
+    return true
+}
+
+/// Verdict is the outcome of an identity test.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Verdict<T> {
+    /// The polynomial evaluates to value at witness, which is nonzero, so the polynomial is
+    /// certainly nonzero. Anyone can check this by evaluating the polynomial at witness.
+    NonZero { witness: Vec<T>, value: T },
+    /// The polynomial evaluated to zero at trials random points. If it is nonzero, this happens
+    /// with probability at most error_bound.
+    ProbablyZero { trials: usize, error_bound: f64 },
+}
+
+impl<T> Verdict<T> {
+    /// Returns whether the test found no evidence that the polynomial is nonzero.
+    pub fn is_probably_zero(&self) -> bool {
+        return matches!(self, Verdict::ProbablyZero { .. })
+    }
+
+    /// Returns the point at which the polynomial was found to be nonzero, if any.
+    pub fn witness(&self) -> Option<&[T]> {
+        match self {
+            Verdict::NonZero { witness, .. } => return Some(witness),
+            Verdict::ProbablyZero { .. } => return None,
+        }
+    }
+}
+
+/// IdentityTest is implemented by polynomials that know where to draw random points from, so
+/// they can be tested for zero without being handed a sample set.
+pub trait IdentityTest<T> {
+    /// Tests whether the polynomial is zero, running enough trials that a nonzero polynomial is
+    /// reported as zero with probability at most error. All randomness is drawn from rng, so a
+    /// seeded rng makes the verdict reproducible.
+    ///
+    /// Returns None if the test refuses to run, for example because the degree is too large for
+    /// the field to bound the error.
+    fn try_test_zero_with_rng<R: Rng + ?Sized>(&self, error: f64, rng: &mut R) -> Option<Verdict<T>>;
+
+    /// Runs `try_test_zero_with_rng`, panicking if the test refuses to run.
+    fn test_zero_with_rng<R: Rng + ?Sized>(&self, error: f64, rng: &mut R) -> Verdict<T> {
+        return self.try_test_zero_with_rng(error, rng).expect("the field is too small to identity test this polynomial")
+    }
+
+    /// Returns whether the polynomial is probably zero, using thread_rng and DEFAULT_ERROR.
+    fn probably_zero(&self) -> bool {
+        return self.test_zero_with_rng(DEFAULT_ERROR, &mut rand::thread_rng()).is_probably_zero()
+    }
+}
+
+/// ProbablyEq is equality decided by an identity test on the difference of two polynomials.
+///
+/// Unlike PartialEq this is allowed to be wrong: two different polynomials are reported equal
+/// with probability at most the given error, and two calls can disagree. Equal polynomials are
+/// always reported equal.
+pub trait ProbablyEq<Rhs: ?Sized = Self> {
+    /// Returns whether the polynomials are probably equal, drawing all randomness from rng.
+    fn probably_eq_with_rng<R: Rng + ?Sized>(&self, other: &Rhs, error: f64, rng: &mut R) -> bool;
+
+    /// Returns whether the polynomials are probably equal, using thread_rng and DEFAULT_ERROR.
+    fn probably_eq(&self, other: &Rhs) -> bool {
+        return self.probably_eq_with_rng(other, DEFAULT_ERROR, &mut rand::thread_rng())
+    }
+}
+
+/// Evaluates the polynomial at `trials` random points drawn using sample, stopping at the first
+/// nonzero evaluation. Every trial is assumed to miss a nonzero polynomial with probability at
+/// most per_trial, so a zero verdict has error bound per_trial^trials.
+pub fn run_trials<T, P, S>(poly: &P, trials: usize, per_trial: f64, mut sample: S) -> Verdict<T> where
+    T: Zero,
+    P: MultivariatePolynomial<T> + ?Sized,
+    S: FnMut() -> T {
+    for _ in 0..trials {
+        let point: Vec<T> = (0..poly.variables()).map(|_| sample()).collect();
+        if let Some(value) = poly.evaluate_at(&point) {
+            if !value.is_zero() {
+                return Verdict::NonZero { witness: point, value }
+            }
+        }
+    }
+    return Verdict::ProbablyZero { trials, error_bound: per_trial.min(1.0).powi(trials as i32) }
+}
+
+/// Runs the Schwartz-Zippel test with as many trials as are needed for a nonzero polynomial to
+/// be reported as zero with probability at most error, drawing every coordinate uniformly from
+/// sample_set.
+///
+/// Returns None if the total degree is not smaller than the size of sample_set, since then a
+/// single trial bounds nothing.
+pub fn test_zero<T, P, S, R>(poly: &P, sample_set: &S, error: f64, rng: &mut R) -> Option<Verdict<T>> where
+    T: Zero,
+    P: MultivariatePolynomial<T> + ?Sized,
+    S: SampleSet<T> + ?Sized,
+    R: Rng + ?Sized {
+    let per_trial = poly.total_degree() as f64 / sample_set.size();
+    let trials = trials_for_ratio(per_trial, error)?;
+    return Some(run_trials(poly, trials, per_trial, || sample_set.sample(rng)))
+}
+
+/// Tests whether the polynomial is zero using the size of the field to get exact bounds.
+///
+/// If the total degree d is at least |F| the test refuses to run and returns None: a nonzero
+/// polynomial like x^p - x can vanish on all of GF(p), so no evaluation over F can tell. Such
+/// polynomials can be tested after lifting them into an extension field.
+///
+/// Otherwise a nonzero polynomial vanishes at a random point with probability at most d/|F|. When
+/// evaluating at every point of F^n takes no more evaluations than the random trials would, every
+/// point is evaluated instead, which is exact since a nonzero polynomial of total degree below
+/// |F| can't vanish on all of F^n. The verdict then has error bound 0.
+pub fn test_zero_over_field<T, P, R>(poly: &P, error: f64, rng: &mut R) -> Option<Verdict<T>> where
+    T: Zero + Clone + FieldInfo + RandomFieldElement,
+    P: MultivariatePolynomial<T> + ?Sized,
+    R: Rng + ?Sized {
+    let degree = poly.total_degree();
+    if let Cardinality::Finite { .. } = T::cardinality() {
+        let per_trial = degree as f64 / T::cardinality().to_f64();
+        let trials = trials_for_ratio(per_trial, error)?;
+        if let Some(elements) = T::elements() {
+            let points = (elements.len() as f64).powi(poly.variables() as i32);
+            if points <= trials as f64 {
+                return Some(exhaustive(poly, &elements))
+            }
+        }
+        return Some(run_trials(poly, trials, per_trial, || T::sample_set().sample(rng)))
+    }
+    return test_zero(poly, &T::sample_set(), error, rng)
+}
+
+/// Panics unless every coefficient belongs to the field that `FieldInfo` describes, since the
+/// bounds of `test_zero_over_field` would otherwise be about the wrong field.
+pub(crate) fn assert_in_field<'a, T, I>(coefficients: I) where
+    T: FieldInfo + 'a,
+    I: IntoIterator<Item=&'a T> {
+    assert!(coefficients.into_iter().all(T::contains), "the coefficients are not in the field being tested over");
+}
+
+/// Evaluates the polynomial at every point whose coordinates are all in elements.
+fn exhaustive<T, P>(poly: &P, elements: &[T]) -> Verdict<T> where
+    T: Zero + Clone,
+    P: MultivariatePolynomial<T> + ?Sized {
+    let variables = poly.variables();
+    // the point is an odometer of indices into elements
+    let mut indices = vec![0; variables];
+    let mut evaluations = 0;
+    loop {
+        let point: Vec<T> = indices.iter().map(|&i| elements[i].clone()).collect();
+        evaluations += 1;
+        if let Some(value) = poly.evaluate_at(&point) {
+            if !value.is_zero() {
+                return Verdict::NonZero { witness: point, value }
+            }
+        }
+        let mut position = 0;
+        while position < variables && indices[position] + 1 == elements.len() {
+            indices[position] = 0;
+            position += 1;
+        }
+        if position == variables {
+            return Verdict::ProbablyZero { trials: evaluations, error_bound: 0.0 }
+        }
+        indices[position] += 1;
+    }
+}
+
+/// Runs the Schwartz-Zippel test: the polynomial is evaluated at `trials` random points, where
+/// every coordinate of every point is drawn independently using sample. Returns false as soon as
+/// one evaluation is nonzero, which proves the polynomial is nonzero.
+///
+/// If sample draws uniformly from a finite set S and the polynomial is nonzero with total degree
+/// d, a single trial evaluates to zero with probability at most d/|S|, so returning true is wrong
+/// with probability at most (d/|S|)^trials.
+pub fn schwartz_zippel<T, P, S>(poly: &P, trials: usize, sample: S) -> bool where
+    T: Zero,
+    P: MultivariatePolynomial<T> + ?Sized,
+    S: FnMut() -> T {
+    return run_trials(poly, trials, 1.0, sample).is_probably_zero()
+}
+
+/// Returns how many trials of the Schwartz-Zippel test are needed so that a nonzero polynomial of
+/// total degree d passes all of them with probability at most error, when every coordinate is
+/// drawn from a set of size set_size.
+///
+/// Returns None if d is not smaller than set_size, since then a single trial bounds nothing.
+pub fn trials_for(degree: usize, set_size: usize, error: f64) -> Option<usize> {
+    if degree >= set_size {
+        return None
+    }
+    return trials_for_ratio(degree as f64 / set_size as f64, error)
+}
+
+/// Returns how many independent trials that each miss with probability at most per_trial are
+/// needed to bring the total error down to error, or None if per_trial is at least one. Panics
+/// if error is not in (0, 1), since no number of trials reaches an error of zero and one needs
+/// none.
+pub(crate) fn trials_for_ratio(per_trial: f64, error: f64) -> Option<usize> {
+    assert!(error > 0.0 && error < 1.0, "error must be in (0, 1)");
+    if per_trial >= 1.0 || per_trial.is_nan() {
+        return None
+    }
+    if per_trial == 0.0 {
+        // a nonzero constant is nonzero everywhere
+        return Some(1)
+    }
+    let trials = (error.ln() / per_trial.ln()).ceil() as usize;
+    return Some(trials.max(1))
+}
+
+#[test]
+fn trials_for_bounds() {
+    assert_eq!(trials_for(0, 10, 0.5), Some(1));
+    assert_eq!(trials_for(1, 10, 0.1), Some(1));
+    assert_eq!(trials_for(1, 10, 0.01), Some(2));
+    assert_eq!(trials_for(5, 10, 0.001), Some(10));
+    assert_eq!(trials_for(10, 10, 0.5), None);
+}
+
+#[test]
+fn schwartz_zippel_on_univariate() {
+    use crate::VecPoly;
+    use crate::field::Fp;
+    use rand::prelude::*;
+
+    let mut rng = StdRng::seed_from_u64(3);
+    // x^2 + 1 has no roots in GF(103) since 103 = 3 mod 4
+    let nonzero = VecPoly::new(vec![Fp::<103>::new(1), Fp::new(0), Fp::new(1)]);
+    assert_eq!(schwartz_zippel(&nonzero, 10, || rng.gen::<Fp<103>>()), false);
+    let zero = VecPoly::new(vec![Fp::<103>::new(0); 3]);
+    assert_eq!(schwartz_zippel(&zero, 10, || rng.gen::<Fp<103>>()), true);
+    assert_eq!(schwartz_zippel(&VecPoly::<Fp<103>>::new(vec![]), 10, || rng.gen::<Fp<103>>()), true);
+}
+
+#[test]
+fn verdicts_carry_witnesses_and_bounds() {
+    use crate::VecPoly;
+    use crate::field::Fp;
+    use crate::sample::RandomFieldElement;
+    use rand::prelude::*;
+
+    let mut rng = StdRng::seed_from_u64(5);
+    // x^2 - 4 over GF(103), whose roots are 2 and 101
+    let nonzero = VecPoly::new(vec![Fp::<103>::new(1), Fp::new(0), Fp::from_i64(-4)]);
+    match test_zero(&nonzero, &Fp::<103>::sample_set(), 1e-6, &mut rng) {
+        Some(Verdict::NonZero { witness, value }) => {
+            assert!(!value.is_zero());
+            assert_eq!(nonzero.evaluate_at(&witness), Some(value));
+        }
+        verdict => panic!("unexpected verdict {:?}", verdict),
+    }
+
+    let zero = VecPoly::<Fp<103>>::new(vec![]);
+    let verdict = run_trials(&zero, 4, 0.5, || rng.gen::<Fp<103>>());
+    assert_eq!(verdict, Verdict::ProbablyZero { trials: 4, error_bound: 0.0625 });
+    assert_eq!(verdict.witness(), None);
+    // the zero polynomial has no degree, so a single trial is exact
+    let verdict = test_zero(&(&nonzero - &nonzero), &Fp::<103>::sample_set(), 1e-6, &mut rng);
+    assert_eq!(verdict, Some(Verdict::ProbablyZero { trials: 1, error_bound: 0.0 }));
+    assert!(test_zero(&nonzero, &[Fp::new(1), Fp::new(2)][..], 1e-6, &mut rng).is_none());
+}
+
+#[test]
+fn field_sized_tests_refuse_or_enumerate() {
+    use crate::VecPoly;
+    use crate::field::Fp;
+    use crate::circuit::Circuit;
+    use crate::multivariate::MultiPoly;
+    use rand::prelude::*;
+
+    let mut rng = StdRng::seed_from_u64(6);
+    // x^5 - x vanishes on all of GF(5), and its degree is too large to test there
+    let fermat = VecPoly::new(vec![Fp::<5>::new(1), Fp::new(0), Fp::new(0), Fp::new(0), Fp::from_i64(-1), Fp::new(0)]);
+    assert_eq!(test_zero_over_field(&fermat, 0.01, &mut rng), None);
+
+    // x(x - 1) vanishes on two of the five points, so evaluating all of GF(5) finds a witness
+    // faster than the 6 random trials needed for error 0.01
+    let product = VecPoly::new(vec![Fp::<5>::new(1), Fp::from_i64(-1), Fp::new(0)]);
+    match test_zero_over_field(&product, 0.01, &mut rng) {
+        Some(Verdict::NonZero { witness, .. }) => assert_eq!(witness, vec![Fp::new(2)]),
+        verdict => panic!("unexpected verdict {:?}", verdict),
+    }
+
+    // the circuit x y - y x computes zero, and evaluating it at all 25 points of GF(5)^2 is
+    // cheaper than the 51 random trials needed for error 1e-20
+    fn commutator<T: Zero + num::One + std::ops::Sub<Output=T>>(circuit: &mut Circuit<T>) {
+        let x = circuit.input(0);
+        let y = circuit.input(1);
+        let xy = circuit.mul(x, y);
+        let yx = circuit.mul(y, x);
+        let difference = circuit.sub(xy, yx);
+        circuit.set_output(difference);
+    }
+    let mut small = Circuit::<Fp<5>>::new();
+    commutator(&mut small);
+    assert_eq!(test_zero_over_field(&small, 1e-20, &mut rng), Some(Verdict::ProbablyZero { trials: 25, error_bound: 0.0 }));
+    let shifted = MultiPoly::from_terms(vec![(vec![1, 1], Fp::<5>::new(1)), (vec![], Fp::new(3))]);
+    assert_eq!(exhaustive(&shifted, &Fp::<5>::elements().unwrap()), Verdict::NonZero { witness: vec![Fp::new(0), Fp::new(0)], value: Fp::new(3) });
+
+    // over a large field the random test reports the exact bound (2/65537)^trials
+    let mut large = Circuit::<Fp<65537>>::new();
+    commutator(&mut large);
+    let bound = (2.0f64 / 65537.0).powi(2);
+    assert_eq!(test_zero_over_field(&large, 1e-9, &mut rng), Some(Verdict::ProbablyZero { trials: 2, error_bound: bound }));
+}
@@ -0,0 +1,201 @@
+//! Polynomials represented by their values at fixed distinct points.
+
+use rand::Rng;
+use crate::{IdentityTest, Polynomial, VecPoly, Verdict};
+use crate::algebra::Field;
+
+/// PointValuePoly is the unique polynomial of degree less than n that takes the given values at
+/// n distinct points. This means that the following polynomial:
+///     x^2 + 2
+/// could be represented with the points [0, 1, 2] by the values [2, 3, 6].
+///
+/// Pipelines that produce evaluations can store them as they are, and two polynomials given at
+/// the same points are added or compared value by value. The barycentric weights are computed
+/// once, so evaluating anywhere else takes O(n) operations.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PointValuePoly<T> {
+    points: Vec<T>,
+    values: Vec<T>,
+    weights: Vec<T>,
+}
+
+impl<T: Field + PartialEq> PointValuePoly<T> {
+    /// Creates the polynomial with the given values at the given points, which takes O(n^2)
+    /// operations. Panics if the numbers of points and values differ or a point is repeated.
+    pub fn new(points: Vec<T>, values: Vec<T>) -> Self {
+        assert_eq!(points.len(), values.len(), "every point needs exactly one value");
+        // w_i = 1 / prod_{j != i} (x_i - x_j)
+        let mut weights = Vec::with_capacity(points.len());
+        for (i, x) in points.iter().enumerate() {
+            let mut product = T::one();
+            for (j, other) in points.iter().enumerate() {
+                if i != j {
+                    let difference = x.clone() - other.clone();
+                    assert!(!difference.is_zero(), "the points must be distinct");
+                    product = product * difference;
+                }
+            }
+            weights.push(T::one() / product);
+        }
+        return Self { points, values, weights }
+    }
+
+    /// Evaluates poly at the points. Panics unless there are more points than the degree of
+    /// poly, since otherwise the values don't determine it.
+    pub fn from_coefficients(poly: &VecPoly<T>, points: Vec<T>) -> Self {
+        assert!(poly.degree().is_none_or(|d| d < points.len()), "{} points can't represent a polynomial of degree {:?}", points.len(), poly.degree());
+        let values = points.iter().map(|x| poly.evaluate(x.clone()).unwrap_or_else(T::zero)).collect();
+        return Self::new(points, values)
+    }
+
+    /// Returns the points the polynomial is represented at.
+    pub fn points(&self) -> &[T] {
+        return &self.points
+    }
+
+    /// Returns the values at the points.
+    pub fn values(&self) -> &[T] {
+        return &self.values
+    }
+
+    /// Returns one less than the number of points, which bounds the degree without
+    /// interpolating, or None if there are no points.
+    pub fn degree_bound(&self) -> Option<usize> {
+        return self.points.len().checked_sub(1)
+    }
+
+    /// Returns the coefficients by Lagrange interpolation, which takes O(n^2) operations. The
+    /// polynomial prod (x - x_j) is built once, and each basis polynomial is the quotient of
+    /// dividing it by one of its linear factors.
+    pub fn to_coefficients(&self) -> VecPoly<T> {
+        let n = self.points.len();
+        // the coefficients of prod (x - x_j), lowest order term first
+        let mut master = vec![T::one()];
+        for x in self.points.iter() {
+            master.insert(0, T::zero());
+            for k in 0..master.len() - 1 {
+                master[k] = master[k].clone() - x.clone() * master[k + 1].clone();
+            }
+        }
+        let mut coefficients = vec![T::zero(); n];
+        for ((x, y), w) in self.points.iter().zip(self.values.iter()).zip(self.weights.iter()) {
+            if y.is_zero() {
+                continue
+            }
+            // synthetic division of the master polynomial by x - x_i
+            let scale = y.clone() * w.clone();
+            let mut carry = T::zero();
+            for k in (0..n).rev() {
+                carry = master[k + 1].clone() + x.clone() * carry;
+                coefficients[k] = coefficients[k].clone() + scale.clone() * carry.clone();
+            }
+        }
+        return VecPoly::from_lowest_first(coefficients)
+    }
+}
+
+impl<T: Field + PartialEq> Polynomial<T> for PointValuePoly<T> {
+    /// Returns the true order, which takes O(n^2) operations to interpolate. Use
+    /// `degree_bound` for the bound that the number of points gives for free.
+    fn order(&self) -> usize {
+        if self.values.iter().all(|y| y.is_zero()) {
+            return 0
+        }
+        return self.to_coefficients().coefficients().len()
+    }
+
+    /// Returns the evaluation of the polynomial at element with the barycentric formula
+    /// l(x) sum w_i y_i / (x - x_i), where l(x) = prod (x - x_i).
+    fn evaluate(&self, element: T) -> Option<T> {
+        if self.values.iter().all(|y| y.is_zero()) {
+            return None
+        }
+        let mut numerator = T::zero();
+        let mut node = T::one();
+        for ((x, y), w) in self.points.iter().zip(self.values.iter()).zip(self.weights.iter()) {
+            let difference = element.clone() - x.clone();
+            if difference.is_zero() {
+                return Some(y.clone())
+            }
+            numerator = numerator + w.clone() * y.clone() / difference.clone();
+            node = node * difference;
+        }
+        return Some(node * numerator)
+    }
+}
+
+/// A polynomial given by more values than its degree is zero exactly when every value is zero,
+/// so the test is deterministic, uses no randomness, and has error bound 0.
+impl<T: Field + PartialEq> IdentityTest<T> for PointValuePoly<T> {
+    fn try_test_zero_with_rng<R: Rng + ?Sized>(&self, _error: f64, _rng: &mut R) -> Option<Verdict<T>> {
+        for (x, y) in self.points.iter().zip(self.values.iter()) {
+            if !y.is_zero() {
+                return Some(Verdict::NonZero { witness: vec![x.clone()], value: y.clone() })
+            }
+        }
+        return Some(Verdict::ProbablyZero { trials: self.points.len(), error_bound: 0.0 })
+    }
+}
+
+#[test]
+fn point_value_conversions_are_lossless() {
+    use crate::field::Fp;
+    use rand::prelude::*;
+
+    let mut rng = StdRng::seed_from_u64(19);
+    for degree in [0usize, 1, 4, 9].iter() {
+        let coefficients: Vec<Fp<65537>> = (0..=*degree).map(|_| rng.gen()).collect();
+        let poly = VecPoly::from_lowest_first(coefficients);
+        let points: Vec<Fp<65537>> = (0..*degree as u64 + 3).map(|i| Fp::new(i * 7 + 1)).collect();
+        let values = PointValuePoly::from_coefficients(&poly, points);
+        assert_eq!(values.to_coefficients(), poly);
+        let x: Fp<65537> = rng.gen();
+        assert_eq!(values.evaluate(x), poly.evaluate(x));
+        assert_eq!(values.evaluate(values.points()[1]), Some(values.values()[1]));
+    }
+    // x^2 + 2 at 0, 1, 2 over the rationals
+    let q = |n: i64| num::BigRational::from_integer(n.into());
+    let parabola = PointValuePoly::new(vec![q(0), q(1), q(2)], vec![q(2), q(3), q(6)]);
+    assert_eq!(parabola.to_coefficients(), VecPoly::new(vec![q(1), q(0), q(2)]));
+    assert_eq!(parabola.evaluate(q(5)), Some(q(27)));
+}
+
+#[test]
+fn point_value_identity_tests_are_exact() {
+    use crate::field::Fp;
+    use rand::prelude::*;
+
+    let mut rng = StdRng::seed_from_u64(20);
+    let points: Vec<Fp<101>> = (0..5).map(Fp::new).collect();
+    let zero = PointValuePoly::new(points.clone(), vec![Fp::new(0); 5]);
+    assert_eq!(zero.test_zero_with_rng(1e-9, &mut rng), Verdict::ProbablyZero { trials: 5, error_bound: 0.0 });
+    assert_eq!(zero.order(), 0);
+    assert_eq!(zero.to_coefficients().degree(), None);
+    // x(x - 1)(x - 2)(x - 3) vanishes at four of the points, but not the fifth
+    let quartic = PointValuePoly::new(points, vec![Fp::new(0), Fp::new(0), Fp::new(0), Fp::new(0), Fp::new(24)]);
+    match quartic.test_zero_with_rng(1e-9, &mut rng) {
+        Verdict::NonZero { witness, value } => assert_eq!(quartic.evaluate(witness[0]), Some(value)),
+        verdict => panic!("unexpected verdict {:?}", verdict),
+    }
+    assert_eq!(quartic.to_coefficients().degree(), Some(4));
+}
+
+#[test]
+fn point_value_degree_is_exact() {
+    use crate::field::Fp;
+
+    // x^2 + 2 at five points has degree 2, though five points could hold degree 4
+    let parabola = VecPoly::new(vec![Fp::<101>::new(1), Fp::new(0), Fp::new(2)]);
+    let values = PointValuePoly::from_coefficients(&parabola, (0..5).map(Fp::new).collect());
+    assert_eq!(values.degree(), Some(2));
+    assert_eq!(values.degree_bound(), Some(4));
+    let constant = PointValuePoly::new(vec![Fp::<101>::new(3), Fp::new(4)], vec![Fp::new(7), Fp::new(7)]);
+    assert_eq!(constant.degree(), Some(0));
+    assert_eq!(PointValuePoly::new(vec![Fp::<101>::new(3)], vec![Fp::new(0)]).degree(), None);
+}
+
+#[test]
+#[should_panic(expected = "the points must be distinct")]
+fn point_value_rejects_repeated_points() {
+    let _ = PointValuePoly::new(vec![1.0, 2.0, 1.0], vec![0.0, 0.0, 0.0]);
+}
@@ -0,0 +1,428 @@
+//! Randomized primality tests.
+//!
+//! Each test picks random bases a in [2, n - 2] and checks a property that every base has when n
+//! is prime. A base without it is a witness that n is composite, which anyone can check again
+//! with the matching `*_witness` function. When n is composite, a random base is a witness with
+//! probability at least 3/4 for Miller-Rabin and at least 1/2 for Solovay-Strassen, so repeating
+//! the test drives the chance of calling a composite prime below any target error. The Fermat
+//! test has the same 1/2 bound except on Carmichael numbers, which only the bases sharing a
+//! factor with n expose.
+
+use rand::prelude::*;
+use num::{BigUint, Integer, Zero};
+use crate::field::{add_mod, mul_mod, pow_mod, sub_mod};
+use crate::pit::trials_for_ratio;
+
+/// Bases for which Miller-Rabin is exact on every n < 2^64, found by Jim Sinclair.
+pub const MILLER_RABIN_BASES_64: [u64; 7] = [2, 325, 9375, 28178, 450775, 9780504, 1795265022];
+
+/// PrimalityCandidate is implemented by the unsigned integer types that can be tested.
+pub trait PrimalityCandidate: Integer + Clone + From<u64> {
+    /// Returns the number of bits of the integer, which is zero for zero.
+    fn bits(&self) -> u64;
+
+    /// Returns self + other mod modulus, assuming both are already reduced.
+    fn add_mod(&self, other: &Self, modulus: &Self) -> Self;
+
+    /// Returns self - other mod modulus, assuming both are already reduced.
+    fn sub_mod(&self, other: &Self, modulus: &Self) -> Self;
+
+    /// Returns self * other mod modulus.
+    fn mul_mod(&self, other: &Self, modulus: &Self) -> Self;
+
+    /// Returns self^exp mod modulus.
+    fn pow_mod(&self, exp: &Self, modulus: &Self) -> Self {
+        let two = Self::from(2);
+        let mut result = Self::one().mod_floor(modulus);
+        let mut square = self.mod_floor(modulus);
+        let mut exp = exp.clone();
+        while !exp.is_zero() {
+            let (rest, bit) = exp.div_rem(&two);
+            if !bit.is_zero() {
+                result = result.mul_mod(&square, modulus);
+            }
+            square = square.mul_mod(&square, modulus);
+            exp = rest;
+        }
+        return result
+    }
+
+    /// Returns a uniformly random integer in [0, bound). Panics if bound is zero.
+    fn random_below<R: Rng + ?Sized>(bound: &Self, rng: &mut R) -> Self;
+}
+
+impl PrimalityCandidate for u64 {
+    fn bits(&self) -> u64 {
+        return 64 - self.leading_zeros() as u64
+    }
+
+    fn add_mod(&self, other: &Self, modulus: &Self) -> Self {
+        return add_mod(*self, *other, *modulus)
+    }
+
+    fn sub_mod(&self, other: &Self, modulus: &Self) -> Self {
+        return sub_mod(*self, *other, *modulus)
+    }
+
+    fn mul_mod(&self, other: &Self, modulus: &Self) -> Self {
+        return mul_mod(*self, *other, *modulus)
+    }
+
+    fn pow_mod(&self, exp: &Self, modulus: &Self) -> Self {
+        return pow_mod(*self, *exp, *modulus)
+    }
+
+    fn random_below<R: Rng + ?Sized>(bound: &Self, rng: &mut R) -> Self {
+        return rng.gen_range(0, *bound)
+    }
+}
+
+impl PrimalityCandidate for BigUint {
+    fn bits(&self) -> u64 {
+        return BigUint::bits(self)
+    }
+
+    fn add_mod(&self, other: &Self, modulus: &Self) -> Self {
+        return (self + other) % modulus
+    }
+
+    fn sub_mod(&self, other: &Self, modulus: &Self) -> Self {
+        if self >= other {
+            return self - other
+        }
+        return modulus - (other - self)
+    }
+
+    fn mul_mod(&self, other: &Self, modulus: &Self) -> Self {
+        return (self * other) % modulus
+    }
+
+    fn pow_mod(&self, exp: &Self, modulus: &Self) -> Self {
+        return self.modpow(exp, modulus)
+    }
+
+    fn random_below<R: Rng + ?Sized>(bound: &Self, rng: &mut R) -> Self {
+        assert!(!bound.is_zero(), "the bound must be positive");
+        let bits = bound.bits();
+        let digits = bits.div_ceil(32) as usize;
+        let top_mask = u32::MAX >> (32 * digits as u64 - bits);
+        // rejection sampling from the smallest power of two above bound takes at most two
+        // attempts on average
+        loop {
+            let mut words: Vec<u32> = (0..digits).map(|_| rng.gen()).collect();
+            words[digits - 1] &= top_mask;
+            let candidate = BigUint::new(words);
+            if &candidate < bound {
+                return candidate
+            }
+        }
+    }
+}
+
+/// Primality is the outcome of a randomized primality test.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Primality<N> {
+    /// witness is a base that proves n composite, which can be checked with the `*_witness`
+    /// function of the test that found it.
+    Composite { witness: N },
+    /// No witness was found in trials random bases. If n is composite, this happens with
+    /// probability at most error_bound, except that the bound of the Fermat test does not cover
+    /// Carmichael numbers, which pass it with probability close to one.
+    ProbablyPrime { trials: usize, error_bound: f64 },
+}
+
+impl<N> Primality<N> {
+    /// Returns whether the test found no evidence that n is composite.
+    pub fn is_probably_prime(&self) -> bool {
+        return matches!(self, Primality::ProbablyPrime { .. })
+    }
+
+    /// Returns the base that proves n composite, if any.
+    pub fn witness(&self) -> Option<&N> {
+        match self {
+            Primality::Composite { witness } => return Some(witness),
+            Primality::ProbablyPrime { .. } => return None,
+        }
+    }
+}
+
+/// Returns the Jacobi symbol (a/n) for odd n, which is -1, 0 or 1. For prime n it is the
+/// Legendre symbol, which tells whether a is a square mod n.
+pub fn jacobi<N: PrimalityCandidate>(a: &N, n: &N) -> i8 {
+    assert!(n.is_odd(), "the Jacobi symbol needs an odd modulus");
+    let (two, three, four, five, eight) = (N::from(2), N::from(3), N::from(4), N::from(5), N::from(8));
+    let mut a = a.mod_floor(n);
+    let mut n = n.clone();
+    let mut result = 1;
+    while !a.is_zero() {
+        // (2/n) is -1 exactly when n is 3 or 5 mod 8
+        while a.is_even() {
+            a = a / two.clone();
+            let r = n.mod_floor(&eight);
+            if r == three || r == five {
+                result = -result;
+            }
+        }
+        // quadratic reciprocity flips the sign when both are 3 mod 4
+        std::mem::swap(&mut a, &mut n);
+        if a.mod_floor(&four) == three && n.mod_floor(&four) == three {
+            result = -result;
+        }
+        a = a.mod_floor(&n);
+    }
+    return if n.is_one() { result } else { 0 }
+}
+
+/// Returns whether a^(n-1) is not 1 mod n, which proves that n is composite by Fermat's little
+/// theorem. A multiple of n is never a witness, since every n fails for it. Panics if n < 2.
+pub fn fermat_witness<N: PrimalityCandidate>(n: &N, a: &N) -> bool {
+    assert!(*n >= N::from(2), "primality is only defined for n >= 2");
+    if a.mod_floor(n).is_zero() {
+        return false
+    }
+    return !a.pow_mod(&(n.clone() - N::one()), n).is_one()
+}
+
+/// Returns whether a proves n composite in the Miller-Rabin test. Writing n - 1 = 2^s d with d
+/// odd, the sequence a^d, a^(2d), ..., a^(n-1) mod n must either start at 1 or reach -1 when n is
+/// prime, since 1 has no other square roots mod a prime. A multiple of n is never a witness.
+/// Panics if n < 2.
+pub fn miller_rabin_witness<N: PrimalityCandidate>(n: &N, a: &N) -> bool {
+    assert!(*n >= N::from(2), "primality is only defined for n >= 2");
+    if a.mod_floor(n).is_zero() {
+        return false
+    }
+    let two = N::from(2);
+    let minus_one = n.clone() - N::one();
+    let mut odd = minus_one.clone();
+    let mut shift = 0;
+    while odd.is_even() {
+        odd = odd / two.clone();
+        shift += 1;
+    }
+    let mut x = a.pow_mod(&odd, n);
+    if x.is_one() || x == minus_one {
+        return false
+    }
+    for _ in 1..shift {
+        x = x.mul_mod(&x, n);
+        if x == minus_one {
+            return false
+        }
+    }
+    return true
+}
+
+/// Returns whether a proves n composite in the Solovay-Strassen test, either by sharing a factor
+/// with n or by failing Euler's criterion a^((n-1)/2) = (a/n) mod n. A multiple of n is never a
+/// witness, and every other base is one for even n > 2, where the Jacobi symbol is undefined.
+/// Panics if n < 2.
+pub fn solovay_strassen_witness<N: PrimalityCandidate>(n: &N, a: &N) -> bool {
+    assert!(*n >= N::from(2), "primality is only defined for n >= 2");
+    if a.mod_floor(n).is_zero() {
+        return false
+    }
+    if n.is_even() {
+        return *n != N::from(2)
+    }
+    let minus_one = n.clone() - N::one();
+    let euler = a.pow_mod(&(minus_one.clone() / N::from(2)), n);
+    return match jacobi(a, n) {
+        0 => true,
+        1 => !euler.is_one(),
+        _ => euler != minus_one,
+    }
+}
+
+/// Runs enough trials of is_witness on random bases that a composite n survives all of them
+/// with probability at most error, given that a random base misses with probability at most
+/// per_trial. Panics if n < 2 or error is not in (0, 1), even when n is too small to need trials.
+fn run_primality_test<N, R, W>(n: &N, per_trial: f64, error: f64, rng: &mut R, is_witness: W) -> Primality<N> where
+    N: PrimalityCandidate,
+    R: Rng + ?Sized,
+    W: Fn(&N, &N) -> bool {
+    assert!(*n >= N::from(2), "primality is only defined for n >= 2");
+    assert!(error > 0.0 && error < 1.0, "error must be in (0, 1)");
+    if *n <= N::from(3) {
+        return Primality::ProbablyPrime { trials: 0, error_bound: 0.0 }
+    }
+    if n.is_even() {
+        // 2 is a witness for every even n > 2 in all three tests
+        return Primality::Composite { witness: N::from(2) }
+    }
+    let trials = trials_for_ratio(per_trial, error).expect("a trial misses with probability below one");
+    let range = n.clone() - N::from(3);
+    for _ in 0..trials {
+        let a = N::random_below(&range, rng) + N::from(2);
+        if is_witness(n, &a) {
+            return Primality::Composite { witness: a }
+        }
+    }
+    return Primality::ProbablyPrime { trials, error_bound: per_trial.powi(trials as i32) }
+}
+
+/// Tests whether n is prime with the Fermat test, drawing bases from rng until a composite n
+/// would pass with probability at most error. The bound assumes that n is not a Carmichael
+/// number, which passes for every base coprime to it. Panics if n < 2 or error is not in (0, 1).
+pub fn fermat_test_with_rng<N: PrimalityCandidate, R: Rng + ?Sized>(n: &N, error: f64, rng: &mut R) -> Primality<N> {
+    return run_primality_test(n, 0.5, error, rng, fermat_witness)
+}
+
+/// Tests whether n is prime with the Miller-Rabin test, drawing bases from rng until a composite
+/// n would pass with probability at most error. Panics if n < 2 or error is not in (0, 1).
+pub fn miller_rabin_test_with_rng<N: PrimalityCandidate, R: Rng + ?Sized>(n: &N, error: f64, rng: &mut R) -> Primality<N> {
+    return run_primality_test(n, 0.25, error, rng, miller_rabin_witness)
+}
+
+/// Tests whether n is prime with the Solovay-Strassen test, drawing bases from rng until a
+/// composite n would pass with probability at most error. Panics if n < 2 or error is not in
+/// (0, 1).
+pub fn solovay_strassen_test_with_rng<N: PrimalityCandidate, R: Rng + ?Sized>(n: &N, error: f64, rng: &mut R) -> Primality<N> {
+    return run_primality_test(n, 0.5, error, rng, solovay_strassen_witness)
+}
+
+/// Returns whether n is prime, using Miller-Rabin with `MILLER_RABIN_BASES_64`, which is exact
+/// for every u64.
+pub fn is_prime(n: u64) -> bool {
+    const SMALL_PRIMES: [u64; 12] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37];
+    if n < 2 {
+        return false
+    }
+    for &p in SMALL_PRIMES.iter() {
+        if n.is_multiple_of(p) {
+            return n == p
+        }
+    }
+    // a base that is a multiple of n is never a witness, and the others still suffice
+    return MILLER_RABIN_BASES_64.iter().all(|a| !miller_rabin_witness(&n, a))
+}
+
+#[test]
+fn is_prime_matches_trial_division() {
+    let trial_division = |n: u64| n >= 2 && (2..).take_while(|d| d * d <= n).all(|d| !n.is_multiple_of(d));
+    for n in 0..3000 {
+        assert_eq!(is_prime(n), trial_division(n), "{}", n);
+    }
+    // strong pseudoprimes to several small bases, and the largest 64 bit prime
+    assert!(!is_prime(3215031751));
+    assert!(!is_prime(3825123056546413051));
+    assert!(is_prime(18446744073709551557));
+    assert!(is_prime(2305843009213693951));
+    // the first twelve primes are also exact as bases below 2^64
+    let mut rng = StdRng::seed_from_u64(36);
+    for _ in 0..20000 {
+        let n = rng.gen::<u64>() | 1;
+        let by_small_bases = [2u64, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37].iter().all(|a| !miller_rabin_witness(&n, a));
+        assert_eq!(is_prime(n), by_small_bases, "{}", n);
+    }
+}
+
+#[test]
+fn randomized_tests_find_witnesses_for_composites() {
+    type Test = fn(&u64, f64, &mut StdRng) -> Primality<u64>;
+    type Witness = fn(&u64, &u64) -> bool;
+
+    let mut rng = StdRng::seed_from_u64(37);
+    let tests: [(Test, Witness); 3] = [
+        (fermat_test_with_rng, fermat_witness),
+        (miller_rabin_test_with_rng, miller_rabin_witness),
+        (solovay_strassen_test_with_rng, solovay_strassen_witness),
+    ];
+    for (test, is_witness) in tests.iter() {
+        for n in 2..2000u64 {
+            match test(&n, 1e-12, &mut rng) {
+                Primality::Composite { witness } => {
+                    assert!(!is_prime(n) && is_witness(&n, &witness), "{} {}", n, witness);
+                }
+                Primality::ProbablyPrime { error_bound, .. } => {
+                    assert!(is_prime(n), "{}", n);
+                    assert!(error_bound <= 1e-12);
+                }
+            }
+        }
+        let p = 4294967291u64;
+        assert!(test(&p, 1e-12, &mut rng).is_probably_prime());
+        assert!(test(&(p * 4294967279), 1e-12, &mut rng).witness().is_some_and(|a| is_witness(&(p * 4294967279), a)));
+    }
+    assert_eq!(miller_rabin_test_with_rng(&3u64, 1e-12, &mut rng), Primality::ProbablyPrime { trials: 0, error_bound: 0.0 });
+    assert_eq!(miller_rabin_test_with_rng(&1_000_000u64, 1e-12, &mut rng), Primality::Composite { witness: 2 });
+}
+
+#[test]
+fn jacobi_symbol_matches_euler_criterion_for_primes() {
+    for &p in [3u64, 5, 7, 11, 13, 101].iter() {
+        for a in 0..p {
+            let squares: Vec<u64> = (1..p).map(|x| x * x % p).collect();
+            let expected = if a == 0 { 0 } else if squares.contains(&a) { 1 } else { -1 };
+            assert_eq!(jacobi(&a, &p), expected, "({}/{})", a, p);
+        }
+    }
+    // (2/15) = (2/3)(2/5) = 1 although 2 is not a square mod 15
+    assert_eq!(jacobi(&2u64, &15), 1);
+    assert_eq!(jacobi(&BigUint::from(1001u32), &BigUint::from(9907u32)), -1);
+}
+
+#[test]
+fn multiples_of_n_are_never_witnesses() {
+    type Witness = fn(&u64, &u64) -> bool;
+
+    for is_witness in [fermat_witness as Witness, miller_rabin_witness, solovay_strassen_witness].iter() {
+        for &n in [2u64, 3, 7, 15, 561].iter() {
+            assert!(!is_witness(&n, &0) && !is_witness(&n, &n) && !is_witness(&n, &(3 * n)), "{}", n);
+        }
+        // bases sharing a factor with a composite n still are
+        assert!(is_witness(&15, &5));
+        assert!(!is_witness(&7, &3));
+    }
+}
+
+#[test]
+fn carmichael_numbers_fool_only_the_fermat_test() {
+    let mut rng = StdRng::seed_from_u64(38);
+    // (6k + 1)(12k + 1)(18k + 1) is a Carmichael number when all three factors are prime
+    let k = (1_000_000u64..).find(|k| is_prime(6 * k + 1) && is_prime(12 * k + 1) && is_prime(18 * k + 1)).unwrap();
+    let carmichael = BigUint::from(6 * k + 1) * BigUint::from(12 * k + 1) * BigUint::from(18 * k + 1);
+    assert!(carmichael.bits() > 64);
+    for _ in 0..5 {
+        let base = BigUint::random_below(&carmichael, &mut rng);
+        assert!(!fermat_witness(&carmichael, &base));
+    }
+    // the error bound of the Fermat test does not cover Carmichael numbers
+    assert!(fermat_test_with_rng(&carmichael, 1e-6, &mut rng).is_probably_prime());
+    let verdict = miller_rabin_test_with_rng(&carmichael, 1e-6, &mut rng);
+    assert!(verdict.witness().is_some_and(|a| miller_rabin_witness(&carmichael, a)));
+    let verdict = solovay_strassen_test_with_rng(&carmichael, 1e-6, &mut rng);
+    assert!(verdict.witness().is_some_and(|a| solovay_strassen_witness(&carmichael, a)));
+
+    for &n in [561u64, 1105, 1729, 2465, 2821, 6601].iter() {
+        assert!((2..n).filter(|a| a.gcd(&n) == 1).all(|a| !fermat_witness(&n, &a)));
+        assert!(!miller_rabin_test_with_rng(&n, 1e-9, &mut rng).is_probably_prime());
+    }
+}
+
+#[test]
+fn big_primes_pass_and_big_composites_fail() {
+    use num::One;
+
+    let mut rng = StdRng::seed_from_u64(39);
+    let one = BigUint::one();
+    let mersenne = (&one << 127usize) - &one;
+    let fermat_number = (&one << 128usize) + &one;
+    for verdict in [
+        fermat_test_with_rng(&mersenne, 1e-20, &mut rng),
+        miller_rabin_test_with_rng(&mersenne, 1e-20, &mut rng),
+        solovay_strassen_test_with_rng(&mersenne, 1e-20, &mut rng),
+    ].iter() {
+        assert!(verdict.is_probably_prime());
+    }
+    assert_eq!(miller_rabin_test_with_rng(&mersenne, 1e-20, &mut rng), Primality::ProbablyPrime { trials: 34, error_bound: 0.25f64.powi(34) });
+    let witness = miller_rabin_test_with_rng(&fermat_number, 1e-20, &mut rng).witness().cloned().unwrap();
+    assert!(miller_rabin_witness(&fermat_number, &witness));
+    assert!(solovay_strassen_test_with_rng(&(&mersenne * &mersenne), 1e-20, &mut rng).witness().is_some());
+}
+
+#[test]
+#[should_panic(expected = "error must be in (0, 1)")]
+fn an_error_of_one_is_rejected_even_for_small_n() {
+    let _ = miller_rabin_test_with_rng(&3u64, 1.0, &mut StdRng::seed_from_u64(42));
+}
@@ -0,0 +1,100 @@
+//! Exact polynomials with rational coefficients.
+//!
+//! Identity tests over f64 report rounding errors as nonzero polynomials, so an identity like
+//! (x + 0.1)^2 = x^2 + 0.2x + 0.01 fails. Converting the coefficients to rationals first makes
+//! every operation and evaluation exact, at the cost of big integer arithmetic.
+
+use num::{BigInt, BigRational, Integer, One, Signed, ToPrimitive, Zero};
+use crate::VecPoly;
+
+/// Returns the rational closest to x whose denominator is at most max_denominator, or None if x
+/// is infinite or NaN.
+///
+/// Floats that were parsed from short decimals are recovered exactly when max_denominator is at
+/// least the power of ten they were written with, so 0.1 becomes 1/10 rather than the binary
+/// value 3602879701896397/36028797018963968 that the float actually holds. The closest
+/// approximation is found among the convergents and semiconvergents of the continued fraction
+/// of the exact value of x.
+pub fn rationalize(x: f64, max_denominator: u64) -> Option<BigRational> {
+    assert!(max_denominator > 0, "the denominator must be positive");
+    let exact = BigRational::from_float(x)?;
+    let max_denominator = BigInt::from(max_denominator);
+    if *exact.denom() <= max_denominator {
+        return Some(exact)
+    }
+    // p0/q0 and p1/q1 are the last two convergents
+    let (mut p0, mut q0, mut p1, mut q1) = (BigInt::zero(), BigInt::one(), BigInt::one(), BigInt::zero());
+    let (mut n, mut d) = (exact.numer().clone(), exact.denom().clone());
+    loop {
+        let a = n.div_floor(&d);
+        let q2 = &q0 + &a * &q1;
+        if q2 > max_denominator {
+            break
+        }
+        let p2 = &p0 + &a * &p1;
+        p0 = std::mem::replace(&mut p1, p2);
+        q0 = std::mem::replace(&mut q1, q2);
+        let remainder = &n - &a * &d;
+        n = std::mem::replace(&mut d, remainder);
+    }
+    // the best semiconvergent with a small enough denominator, against the last convergent
+    let k = (&max_denominator - &q0).div_floor(&q1);
+    let semiconvergent = BigRational::new(&p0 + &k * &p1, &q0 + &k * &q1);
+    let convergent = BigRational::new(p1, q1);
+    if (&semiconvergent - &exact).abs() < (&convergent - &exact).abs() {
+        return Some(semiconvergent)
+    }
+    return Some(convergent)
+}
+
+impl VecPoly<BigRational> {
+    /// Converts a float polynomial to an exact one, replacing every coefficient with the closest
+    /// rational whose denominator is at most max_denominator, see `rationalize`. Returns None if
+    /// a coefficient is infinite or NaN.
+    pub fn from_floats(poly: &VecPoly<f64>, max_denominator: u64) -> Option<Self> {
+        let coefficients = poly.coefficients().iter().map(|&c| rationalize(c, max_denominator)).collect::<Option<Vec<_>>>()?;
+        return Some(Self::from_lowest_first(coefficients))
+    }
+
+    /// Converts the coefficients back to the closest floats.
+    pub fn to_floats(&self) -> VecPoly<f64> {
+        return VecPoly::from_lowest_first(self.coefficients.iter().map(|c| c.to_f64().unwrap_or(f64::NAN)).collect())
+    }
+}
+
+#[test]
+fn rationalize_recovers_short_decimals() {
+    let q = |n: i64, d: i64| BigRational::new(n.into(), d.into());
+    assert_eq!(rationalize(0.1, 1000), Some(q(1, 10)));
+    assert_eq!(rationalize(-2.75, 1000), Some(q(-11, 4)));
+    assert_eq!(rationalize(0.1 + 0.2, 1000), Some(q(3, 10)));
+    assert_eq!(rationalize(std::f64::consts::PI, 1000), Some(q(355, 113)));
+    assert_eq!(rationalize(std::f64::consts::PI, 7), Some(q(22, 7)));
+    assert_eq!(rationalize(3.0, 1), Some(q(3, 1)));
+    assert_eq!(rationalize(f64::NAN, 10), None);
+    // with a denominator of 2^55 allowed, 0.1 is its exact binary value
+    assert_eq!(rationalize(0.1, 1 << 55), BigRational::from_float(0.1));
+}
+
+#[test]
+fn float_identities_hold_exactly_after_conversion() {
+    use crate::{IdentityTest, ProbablyEq};
+    use rand::prelude::*;
+
+    let mut rng = StdRng::seed_from_u64(16);
+    // (x + 0.1)^2 against x^2 + 0.2x + 0.01, computed in floats
+    let float_square = VecPoly::new(vec![1.0, 0.1]).pow(2);
+    let float_expanded = VecPoly::new(vec![1.0, 0.2, 0.01]);
+    assert!(!(&float_square - &float_expanded).test_zero_with_rng(1e-9, &mut rng).is_probably_zero());
+
+    let square = VecPoly::from_floats(&VecPoly::new(vec![1.0, 0.1]), 1000).unwrap().pow(2);
+    let expanded = VecPoly::from_floats(&float_expanded, 1000).unwrap();
+    assert!(square.probably_eq_with_rng(&expanded, 1e-9, &mut rng));
+    assert!(square == expanded);
+    assert_eq!(expanded.to_floats().coefficients(), float_expanded.coefficients());
+
+    // exact evaluation at a rational point: (1/3 + 1/10)^2 = 169/900
+    let x = BigRational::new(1.into(), 3.into());
+    assert_eq!(crate::Polynomial::evaluate(&square, x), Some(BigRational::new(169.into(), 900.into())));
+    assert!(!(&square - &VecPoly::new(vec![BigRational::one()])).probably_zero());
+}
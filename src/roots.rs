@@ -0,0 +1,156 @@
+//! Roots of polynomials over finite fields.
+//!
+//! Every element of GF(q) is a root of x^q - x, so gcd(f, x^q - x) is the product of the distinct
+//! linear factors of f. Computing x^q mod f by repeated squaring takes O(log q) products of
+//! polynomials of degree less than that of f, so this never touches the q elements one by one.
+//! The gcd is then split into its linear factors with Cantor-Zassenhaus.
+
+use rand::prelude::*;
+use num::BigUint;
+use crate::{Polynomial, VecPoly};
+use crate::algebra::Field;
+use crate::field::{Cardinality, FieldInfo};
+use crate::sample::RandomFieldElement;
+
+impl<T> VecPoly<T> where
+    T: Field + PartialEq + FieldInfo + RandomFieldElement {
+    /// Returns how many times x - root divides the polynomial, which is zero unless root is a
+    /// root. Panics if the polynomial is zero.
+    pub fn root_multiplicity(&self, root: &T) -> usize {
+        assert!(self.degree().is_some(), "every element is a root of the zero polynomial");
+        let linear = VecPoly::from_lowest_first(vec![T::zero() - root.clone(), T::one()]);
+        let mut multiplicity = 0;
+        let mut rest = self.clone();
+        loop {
+            let (quotient, remainder) = rest.div_rem(&linear);
+            if remainder.degree().is_some() {
+                return multiplicity
+            }
+            multiplicity += 1;
+            rest = quotient;
+        }
+    }
+
+    /// Returns the distinct roots of the polynomial in GF(q) with their multiplicities, drawing
+    /// the randomness for splitting from rng. The order of the roots depends on rng. Panics if
+    /// the polynomial is zero or the field is infinite.
+    pub fn roots_with_rng<R: Rng + ?Sized>(&self, rng: &mut R) -> Vec<(T, usize)> {
+        assert!(self.degree().is_some(), "every element is a root of the zero polynomial");
+        let q = match T::cardinality() {
+            Cardinality::Finite { characteristic, degree } => num::pow(BigUint::from(characteristic), degree as usize),
+            Cardinality::Infinite => panic!("root finding needs a finite field"),
+        };
+        let monic = self.monic();
+        if monic.degree() == Some(0) {
+            return vec![]
+        }
+        let x = VecPoly::from_lowest_first(vec![T::zero(), T::one()]);
+        let x_to_q = x.pow_mod(&q, &monic);
+        let linear_part = monic.gcd(&(&x_to_q - &x));
+        if linear_part.degree() == Some(0) {
+            return vec![]
+        }
+        return linear_part.equal_degree_factorization_with_rng(1, rng).into_iter().map(|factor| {
+            let root = T::zero() - factor.coefficients()[0].clone();
+            let multiplicity = self.root_multiplicity(&root);
+            (root, multiplicity)
+        }).collect()
+    }
+
+    /// Runs `roots_with_rng` with thread_rng.
+    pub fn roots(&self) -> Vec<(T, usize)> {
+        return self.roots_with_rng(&mut rand::thread_rng())
+    }
+
+    /// Returns the roots with their multiplicities by evaluating the polynomial at every element
+    /// of the field, in the order of `FieldInfo::elements`. This is slow but obviously correct,
+    /// so it serves as an oracle in tests. Returns None if the field is too large to enumerate.
+    /// Panics if the polynomial is zero.
+    pub fn roots_exhaustive(&self) -> Option<Vec<(T, usize)>> {
+        assert!(self.degree().is_some(), "every element is a root of the zero polynomial");
+        let elements = T::elements()?;
+        return Some(elements.into_iter()
+            .filter(|a| self.evaluate(a.clone()).is_some_and(|v| v.is_zero()))
+            .map(|a| {
+                let multiplicity = self.root_multiplicity(&a);
+                (a, multiplicity)
+            })
+            .collect())
+    }
+}
+
+/// Sorts roots into the order of the field's elements, so the output of `roots_with_rng` can be
+/// compared with `roots_exhaustive`.
+#[cfg(test)]
+fn in_field_order<T: FieldInfo + PartialEq>(mut roots: Vec<(T, usize)>) -> Vec<(T, usize)> {
+    let elements = T::elements().unwrap();
+    roots.sort_by_key(|(root, _)| elements.iter().position(|a| a == root));
+    return roots
+}
+
+#[test]
+fn roots_with_multiplicities_over_a_large_field() {
+    use crate::field::Fp;
+    type F = Fp<2305843009213693951>;
+
+    let mut rng = StdRng::seed_from_u64(32);
+    // 3 (x - 5)^2 (x - 10^18) (x + 1)^3 (x^2 + 1), where x^2 + 1 has no roots since the
+    // modulus is 3 mod 4
+    let linear = |r: F| VecPoly::new(vec![F::new(1), F::new(0) - r]);
+    let expected = [(F::new(5), 2), (F::new(1_000_000_000_000_000_000), 1), (F::from_i64(-1), 3)];
+    let mut poly = VecPoly::new(vec![F::new(3), F::new(0), F::new(3)]);
+    for (root, multiplicity) in expected.iter() {
+        poly *= linear(*root).pow(*multiplicity as u64);
+    }
+    let roots = poly.roots_with_rng(&mut rng);
+    assert_eq!(roots.len(), 3);
+    for pair in expected.iter() {
+        assert!(roots.contains(pair), "{:?} is missing from {:?}", pair, roots);
+    }
+    assert_eq!(VecPoly::new(vec![F::new(1), F::new(0), F::new(1)]).roots_with_rng(&mut rng), vec![]);
+    assert_eq!(VecPoly::new(vec![F::new(7)]).roots(), vec![]);
+}
+
+#[test]
+fn randomized_roots_match_the_exhaustive_oracle() {
+    use num::One;
+    use crate::field::{Fp, GF256};
+
+    let mut rng = StdRng::seed_from_u64(33);
+    for _ in 0..30 {
+        let degree = rng.gen_range(1, 12);
+        let poly = VecPoly::from_lowest_first((0..=degree).map(|_| rng.gen::<Fp<13>>()).collect());
+        if poly.degree().is_none() {
+            continue
+        }
+        assert_eq!(in_field_order(poly.roots_with_rng(&mut rng)), poly.roots_exhaustive().unwrap());
+
+        // products of linear factors have many repeated roots
+        let split: VecPoly<GF256> = (0..degree).fold(VecPoly::one(), |acc, _| &acc * &VecPoly::new(vec![GF256::new(1), GF256::new(rng.gen_range(0, 6))]));
+        assert_eq!(in_field_order(split.roots_with_rng(&mut rng)), split.roots_exhaustive().unwrap());
+    }
+    // every element of GF(13) is a root of x^13 - x
+    let mut coefficients = vec![Fp::<13>::new(0); 14];
+    coefficients[1] = Fp::from_i64(-1);
+    coefficients[13] = Fp::new(1);
+    let fermat = VecPoly::from_lowest_first(coefficients);
+    assert_eq!(in_field_order(fermat.roots_with_rng(&mut rng)), (0..13).map(|a| (Fp::new(a), 1)).collect::<Vec<_>>());
+    assert_eq!(VecPoly::<Fp<65537>>::new(vec![Fp::new(1), Fp::new(0)]).roots_exhaustive(), None);
+}
+
+#[test]
+#[should_panic(expected = "every element is a root of the zero polynomial")]
+fn roots_of_the_zero_polynomial_panic() {
+    use crate::field::Fp;
+
+    VecPoly::<Fp<13>>::from_lowest_first(vec![]).roots_with_rng(&mut StdRng::seed_from_u64(34));
+}
+
+#[test]
+#[should_panic(expected = "every element is a root of the zero polynomial")]
+fn exhaustive_roots_of_the_zero_polynomial_panic() {
+    use crate::field::Fp;
+
+    // the field is too large to enumerate, but the zero polynomial is rejected first
+    VecPoly::<Fp<65537>>::from_lowest_first(vec![]).roots_exhaustive();
+}
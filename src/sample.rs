@@ -0,0 +1,311 @@
+//! Sets that identity tests draw their random points from.
+
+use rand::prelude::*;
+use rand::distributions::Standard;
+use num::{Zero, Integer, BigInt, BigRational};
+use std::marker::PhantomData;
+use crate::VecPoly;
+
+/// SampleSet is a finite set of elements that can be sampled uniformly at random.
+///
+/// By the Schwartz-Zippel lemma, a nonzero polynomial of total degree d vanishes at a point drawn
+/// uniformly from S^n with probability at most d/|S|, so the size of the set is what bounds the
+/// error of an identity test.
+pub trait SampleSet<T> {
+    /// Returns the number of elements in the set. This is a float so that sets too large for a
+    /// usize, like GF(p^k) for large k, can be described.
+    fn size(&self) -> f64;
+
+    /// Draws an element of the set uniformly at random.
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> T;
+}
+
+/// A slice is the explicit finite set of its elements. Repeated elements are drawn more often,
+/// so the elements should be distinct for the error bound to hold.
+impl<T: Clone> SampleSet<T> for [T] {
+    fn size(&self) -> f64 {
+        return self.len() as f64
+    }
+
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> T {
+        return self.choose(rng).expect("sample set is empty").clone()
+    }
+}
+
+impl<T: Clone> SampleSet<T> for Vec<T> {
+    fn size(&self) -> f64 {
+        return self.as_slice().size()
+    }
+
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> T {
+        return self.as_slice().sample(rng)
+    }
+}
+
+/// WholeField is every element of a finite field, sampled with its Standard distribution, which
+/// has to be uniform.
+pub struct WholeField<T> {
+    size: f64,
+    element: PhantomData<fn() -> T>,
+}
+
+impl<T> WholeField<T> {
+    /// Creates the set of all elements of a field with size elements.
+    pub fn new(size: f64) -> Self {
+        return Self { size, element: PhantomData }
+    }
+}
+
+impl<T> SampleSet<T> for WholeField<T> where
+    Standard: Distribution<T> {
+    fn size(&self) -> f64 {
+        return self.size
+    }
+
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> T {
+        return rng.gen()
+    }
+}
+
+/// FloatRange samples floats uniformly from [-radius, radius).
+///
+/// Keeping the magnitude small keeps evaluation of high degree polynomials from overflowing to
+/// infinity. The sampler fills the mantissa with random bits, so it draws from 2^52 equally
+/// spaced values for f64 and 2^23 for f32, which is the size used for the error bound.
+pub struct FloatRange<T> {
+    radius: T,
+}
+
+impl<T> FloatRange<T> {
+    /// Creates the range [-radius, radius).
+    pub fn new(radius: T) -> Self {
+        return Self { radius }
+    }
+}
+
+impl SampleSet<f64> for FloatRange<f64> {
+    fn size(&self) -> f64 {
+        return 2f64.powi(52)
+    }
+
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> f64 {
+        return rng.gen_range(-self.radius, self.radius)
+    }
+}
+
+impl SampleSet<f32> for FloatRange<f32> {
+    fn size(&self) -> f64 {
+        return 2f64.powi(23)
+    }
+
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> f32 {
+        return rng.gen_range(-self.radius, self.radius)
+    }
+}
+
+/// Constants embeds a sample set of T into a ring that contains T, like the constant polynomials
+/// in T[x]. The size stays the same.
+pub struct Constants<S> {
+    inner: S,
+}
+
+impl<S> Constants<S> {
+    /// Creates the set of constants whose values are drawn from inner.
+    pub fn new(inner: S) -> Self {
+        return Self { inner }
+    }
+}
+
+impl<T: Zero, S: SampleSet<T>> SampleSet<VecPoly<T>> for Constants<S> {
+    fn size(&self) -> f64 {
+        return self.inner.size()
+    }
+
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> VecPoly<T> {
+        return VecPoly::from_lowest_first(vec![self.inner.sample(rng)])
+    }
+}
+
+/// IntegerRange is the integers in [low, high), converted into T.
+pub struct IntegerRange<T> {
+    low: i64,
+    high: i64,
+    element: PhantomData<fn() -> T>,
+}
+
+impl<T> IntegerRange<T> {
+    /// Creates the range [low, high). Panics if it is empty.
+    pub fn new(low: i64, high: i64) -> Self {
+        assert!(low < high, "the range [{}, {}) is empty", low, high);
+        return Self { low, high, element: PhantomData }
+    }
+}
+
+impl<T: From<i64>> SampleSet<T> for IntegerRange<T> {
+    fn size(&self) -> f64 {
+        return self.high as f64 - self.low as f64
+    }
+
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> T {
+        return T::from(rng.gen_range(self.low, self.high))
+    }
+}
+
+/// RationalRange is the rationals n/d in lowest terms with |n| <= height and 1 <= d <= height.
+///
+/// Every rational has one such representation, so drawing a pair uniformly and rejecting the
+/// ones that aren't in lowest terms samples the set uniformly. About 6/pi^2 of the pairs are
+/// accepted. The size is counted exactly with a totient sieve, which takes O(height) time.
+pub struct RationalRange {
+    height: u32,
+    size: f64,
+}
+
+impl RationalRange {
+    /// Creates the set of rationals of height at most height. Panics if height is zero.
+    pub fn new(height: u32) -> Self {
+        assert!(height > 0, "the height must be positive");
+        let mut totient: Vec<u64> = (0..=height as u64).collect();
+        for k in 2..=height as usize {
+            if totient[k] == k as u64 {
+                for multiple in (k..=height as usize).step_by(k) {
+                    totient[multiple] -= totient[multiple] / k as u64;
+                }
+            }
+        }
+        // pairs in [1, height]^2 with gcd 1, once for each sign, plus 0/1
+        let coprime_pairs = 2 * totient[1..].iter().sum::<u64>() - 1;
+        return Self { height, size: 2.0 * coprime_pairs as f64 + 1.0 }
+    }
+}
+
+impl SampleSet<BigRational> for RationalRange {
+    fn size(&self) -> f64 {
+        return self.size
+    }
+
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> BigRational {
+        let height = self.height as i64;
+        loop {
+            let numerator = rng.gen_range(-height, height + 1);
+            let denominator = rng.gen_range(1, height + 1);
+            if numerator.gcd(&denominator) == 1 || (numerator == 0 && denominator == 1) {
+                return BigRational::new(numerator.into(), denominator.into())
+            }
+        }
+    }
+}
+
+/// RandomFieldElement is implemented by types that have a default set for identity tests to draw
+/// points from: the whole field for finite fields, a bounded range for floats, and a range of
+/// integers for the integers.
+pub trait RandomFieldElement: Sized {
+    /// The type of the default sample set.
+    type SampleSet: SampleSet<Self>;
+
+    /// Returns the default sample set.
+    fn sample_set() -> Self::SampleSet;
+}
+
+/// Floats are drawn from [-1, 1), where powers never overflow.
+impl RandomFieldElement for f64 {
+    type SampleSet = FloatRange<f64>;
+
+    fn sample_set() -> FloatRange<f64> {
+        return FloatRange::new(1.0)
+    }
+}
+
+impl RandomFieldElement for f32 {
+    type SampleSet = FloatRange<f32>;
+
+    fn sample_set() -> FloatRange<f32> {
+        return FloatRange::new(1.0)
+    }
+}
+
+/// Big integers are drawn from [-2^31, 2^31), so evaluation stays cheap while a trial misses a
+/// nonzero polynomial of degree d with probability at most d/2^32.
+impl RandomFieldElement for BigInt {
+    type SampleSet = IntegerRange<BigInt>;
+
+    fn sample_set() -> IntegerRange<BigInt> {
+        return IntegerRange::new(-(1 << 31), 1 << 31)
+    }
+}
+
+/// Rationals are drawn with height at most 2^16, so a trial misses a nonzero polynomial of degree
+/// d with probability at most d/(5 * 10^9) while the samples stay small.
+impl RandomFieldElement for BigRational {
+    type SampleSet = RationalRange;
+
+    fn sample_set() -> RationalRange {
+        return RationalRange::new(1 << 16)
+    }
+}
+
+#[test]
+fn explicit_sets_sample_their_elements() {
+    let set = vec![2, 3, 5, 7];
+    let mut rng = StdRng::seed_from_u64(1);
+    assert_eq!(set.size(), 4.0);
+    assert_eq!(set[..2].size(), 2.0);
+    for _ in 0..100 {
+        assert!(set.contains(&set.sample(&mut rng)));
+    }
+}
+
+#[test]
+fn float_range_stays_bounded() {
+    use crate::Polynomial;
+
+    let mut rng = StdRng::seed_from_u64(2);
+    let set = f64::sample_set();
+    // x^1000 overflows for any sample of magnitude above about 2^1.02, but never on [-1, 1)
+    let mut coefficients = vec![0.0; 1001];
+    coefficients[0] = 1.0;
+    let poly = VecPoly::new(coefficients);
+    for _ in 0..100 {
+        let x = set.sample(&mut rng);
+        assert!((-1.0..1.0).contains(&x));
+        assert!(poly.evaluate(x).unwrap().is_finite());
+    }
+    let x = f32::sample_set().sample(&mut rng);
+    assert!((-1.0..1.0).contains(&x));
+}
+
+#[test]
+fn integer_ranges_and_constants() {
+    let mut rng = StdRng::seed_from_u64(3);
+    let range = IntegerRange::<BigInt>::new(-3, 4);
+    assert_eq!(range.size(), 7.0);
+    for _ in 0..100 {
+        let n = range.sample(&mut rng);
+        assert!(n >= BigInt::from(-3) && n < BigInt::from(4));
+    }
+    let constants = Constants::new(vec![1i64, 2]);
+    assert_eq!(SampleSet::<VecPoly<i64>>::size(&constants), 2.0);
+    let c: VecPoly<i64> = constants.sample(&mut rng);
+    assert!(c.coefficients() == [1] || c.coefficients() == [2]);
+}
+
+#[test]
+fn rational_ranges_count_and_sample_reduced_fractions() {
+    use num::Signed;
+
+    // {-1, 0, 1} and {-2, -1, -1/2, 0, 1/2, 1, 2}
+    assert_eq!(RationalRange::new(1).size(), 3.0);
+    assert_eq!(RationalRange::new(2).size(), 7.0);
+    let range = RationalRange::new(10);
+    let distinct: std::collections::HashSet<BigRational> = (-10..=10i64)
+        .flat_map(|n| (1..=10i64).map(move |d| BigRational::new(n.into(), d.into())))
+        .collect();
+    assert_eq!(range.size(), distinct.len() as f64);
+    let mut rng = StdRng::seed_from_u64(4);
+    for _ in 0..100 {
+        let q = range.sample(&mut rng);
+        assert!(distinct.contains(&q));
+        assert!(q.numer().abs() <= BigInt::from(10) && *q.denom() <= BigInt::from(10));
+    }
+    assert!(BigRational::sample_set().size() > 5e9);
+}
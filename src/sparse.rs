@@ -0,0 +1,204 @@
+//! Sparse univariate polynomials, which only store their nonzero terms.
+
+use rand::prelude::*;
+use num::{Zero, One};
+use std::collections::BTreeMap;
+use std::ops::{Add, Sub, Mul};
+use crate::{IdentityTest, MultivariatePolynomial, Polynomial, VecPoly, Verdict, pit};
+use crate::field::FieldInfo;
+use crate::multivariate::pow;
+use crate::sample::RandomFieldElement;
+
+/// SparsePoly is a polynomial in one variable with coefficients of type T, stored as a map from
+/// exponents to nonzero coefficients. This means that the following polynomial:
+///     x^1000000 + 2
+/// is represented by {0: 2, 1000000: 1}, where a VecPoly would store a million zeros.
+///
+/// Only nonzero coefficients are stored, so the representation is canonical and `==` is exact.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SparsePoly<T> {
+    terms: BTreeMap<usize, T>,
+}
+
+impl<T: Zero + Clone> SparsePoly<T> {
+    /// Creates a polynomial from (exponent, coefficient) pairs. Coefficients of repeated
+    /// exponents are added together.
+    pub fn from_terms<I: IntoIterator<Item=(usize, T)>>(terms: I) -> Self {
+        let mut poly = Self::zero();
+        for (exponent, coefficient) in terms {
+            poly.add_term(exponent, coefficient);
+        }
+        return poly
+    }
+
+    /// Returns an iterator over the (exponent, coefficient) pairs of the nonzero terms, lowest
+    /// exponent first.
+    pub fn terms(&self) -> impl Iterator<Item=(usize, &T)> {
+        return self.terms.iter().map(|(exponent, coefficient)| (*exponent, coefficient))
+    }
+
+    fn add_term(&mut self, exponent: usize, coefficient: T) {
+        let sum = match self.terms.get(&exponent) {
+            Some(existing) => existing.clone() + coefficient,
+            None => coefficient,
+        };
+        if sum.is_zero() {
+            self.terms.remove(&exponent);
+        } else {
+            self.terms.insert(exponent, sum);
+        }
+    }
+}
+
+impl<T> Polynomial<T> for SparsePoly<T> where
+    T: Add<Output=T> + Mul<Output=T> + One + Clone {
+    /// The order is one more than the largest exponent.
+    fn order(&self) -> usize {
+        return self.terms.keys().next_back().map_or(0, |exponent| exponent + 1)
+    }
+
+    /// Returns the evaluation of the polynomial at element with Horner's method over the
+    /// nonzero terms, raising element to the gap between consecutive exponents by repeated
+    /// squaring. This takes O(t log d) multiplications for t terms and degree d.
+    fn evaluate(&self, element: T) -> Option<T> {
+        let mut terms = self.terms.iter().rev();
+        let (mut previous, top) = terms.next()?;
+        let mut accumulated = top.clone();
+        for (exponent, coefficient) in terms {
+            accumulated = accumulated * pow(element.clone(), previous - exponent) + coefficient.clone();
+            previous = exponent;
+        }
+        return Some(accumulated * pow(element, *previous))
+    }
+}
+
+impl<T> MultivariatePolynomial<T> for SparsePoly<T> where
+    T: Add<Output=T> + Mul<Output=T> + One + Clone {
+    fn variables(&self) -> usize {
+        return 1
+    }
+
+    fn total_degree(&self) -> usize {
+        return self.order().saturating_sub(1)
+    }
+
+    fn evaluate_at(&self, point: &[T]) -> Option<T> {
+        return self.evaluate(point[0].clone())
+    }
+}
+
+/// Tests the polynomial over its field of coefficients without expanding it, see
+/// `pit::test_zero_over_field`. Evaluation is fast for any degree, but the degree still has to
+/// be smaller than the field.
+impl<T> IdentityTest<T> for SparsePoly<T> where
+    T: Add<Output=T> + Mul<Output=T> + Zero + One + Clone + FieldInfo + RandomFieldElement {
+    fn try_test_zero_with_rng<R: Rng + ?Sized>(&self, error: f64, rng: &mut R) -> Option<Verdict<T>> {
+        pit::assert_in_field(self.terms.values());
+        return pit::test_zero_over_field(self, error, rng)
+    }
+}
+
+impl<T: Zero + Clone> Add for SparsePoly<T> {
+    type Output=Self;
+
+    fn add(mut self, other: Self) -> Self {
+        for (exponent, coefficient) in other.terms {
+            self.add_term(exponent, coefficient);
+        }
+        return self
+    }
+}
+
+impl<T: Zero + Sub<Output=T> + Clone> Sub for SparsePoly<T> {
+    type Output=Self;
+
+    fn sub(mut self, other: Self) -> Self {
+        for (exponent, coefficient) in other.terms {
+            self.add_term(exponent, T::zero() - coefficient);
+        }
+        return self
+    }
+}
+
+impl<T: Zero + Mul<Output=T> + Clone> Mul for SparsePoly<T> {
+    type Output=Self;
+
+    fn mul(self, other: Self) -> Self {
+        let mut product = Self::zero();
+        for (left, a) in self.terms.iter() {
+            for (right, b) in other.terms.iter() {
+                product.add_term(left + right, a.clone() * b.clone());
+            }
+        }
+        return product
+    }
+}
+
+/// Since zero coefficients are never stored, this check is exact. Use `IdentityTest` for the
+/// randomized test.
+impl<T: Zero + Clone> Zero for SparsePoly<T> {
+    fn zero() -> Self {
+        return Self { terms: BTreeMap::new() }
+    }
+
+    fn is_zero(&self) -> bool {
+        return self.terms.is_empty()
+    }
+}
+
+impl<T: Zero + Clone> From<&VecPoly<T>> for SparsePoly<T> {
+    fn from(poly: &VecPoly<T>) -> Self {
+        return Self::from_terms(poly.coefficients.iter().cloned().enumerate())
+    }
+}
+
+impl<T: Zero + Clone> From<&SparsePoly<T>> for VecPoly<T> {
+    /// Expands the polynomial, which takes memory proportional to its degree.
+    fn from(poly: &SparsePoly<T>) -> Self {
+        let order = poly.terms.keys().next_back().map_or(0, |exponent| exponent + 1);
+        let mut coefficients = vec![T::zero(); order];
+        for (exponent, coefficient) in poly.terms.iter() {
+            coefficients[*exponent] = coefficient.clone();
+        }
+        return VecPoly::from_lowest_first(coefficients)
+    }
+}
+
+#[test]
+fn sparse_conversions_are_lossless() {
+    let dense = VecPoly::new(vec![3, 0, 0, -1, 0, 7]);
+    let sparse = SparsePoly::from(&dense);
+    assert_eq!(sparse.terms().collect::<Vec<_>>(), vec![(0, &7), (2, &-1), (5, &3)]);
+    assert_eq!(VecPoly::from(&sparse), dense);
+    assert_eq!(sparse.degree(), Some(5));
+    assert_eq!(sparse.evaluate(2), dense.evaluate(2));
+    assert_eq!(SparsePoly::from(&VecPoly::<i64>::new(vec![])), SparsePoly::zero());
+    assert_eq!(VecPoly::from(&SparsePoly::<i64>::zero()).degree(), None);
+    // repeated exponents add up, and cancelling terms disappear
+    let cancelled = SparsePoly::from_terms(vec![(4, 2), (1, 1), (4, -2)]);
+    assert_eq!(cancelled, SparsePoly::from_terms(vec![(1, 1)]));
+}
+
+#[test]
+fn huge_sparse_polynomials_are_identity_tested() {
+    use crate::field::Fp;
+    type F = Fp<2305843009213693951>;
+
+    let mut rng = StdRng::seed_from_u64(18);
+    // (x^(10^12) + 1)(x^(10^12) - 1) - (x^(2*10^12) - 1) would need terabytes as a VecPoly
+    let big = 1_000_000_000_000;
+    let plus = SparsePoly::from_terms(vec![(big, F::one()), (0, F::one())]);
+    let minus = SparsePoly::from_terms(vec![(big, F::one()), (0, F::from_i64(-1))]);
+    let square = SparsePoly::from_terms(vec![(2 * big, F::one()), (0, F::from_i64(-1))]);
+    let difference = plus.clone() * minus.clone() - square.clone();
+    assert!(difference.is_zero());
+    let nonzero = plus * minus - square - SparsePoly::from_terms(vec![(big, F::one())]);
+    match nonzero.test_zero_with_rng(1e-9, &mut rng) {
+        Verdict::NonZero { witness, value } => assert_eq!(nonzero.evaluate(witness[0]), Some(value)),
+        verdict => panic!("unexpected verdict {:?}", verdict),
+    }
+    // x^p - x vanishes on all of GF(p), so a degree of p is refused
+    let fermat = SparsePoly::from_terms(vec![(101, Fp::<101>::one()), (1, Fp::from_i64(-1))]);
+    assert!(fermat.evaluate(Fp::new(37)).is_some_and(|v| v.is_zero()));
+    assert_eq!(fermat.try_test_zero_with_rng(1e-9, &mut rng), None);
+}